@@ -0,0 +1,16 @@
+; Formatting rules for Godot 3 syntax, only used with --godot-version 3. They
+; complement the rules in gdscript.scm, which target Godot 4.
+
+; Godot 3 uses keywords where Godot 4 uses annotations
+["tool" "onready" "export" "setget"] @append_space
+"setget" @prepend_space
+
+; export(int, 0, 10) var speed
+(export_variable_statement "export" @append_antispace . (arguments))
+(export_variable_statement (arguments) @append_space)
+
+; Like other statements, the Godot 3 declarations end their line
+([(tool_statement) (export_variable_statement) (onready_variable_statement)]
+@append_empty_softline . (comment)? @do_nothing)
+([(tool_statement) (export_variable_statement) (onready_variable_statement)]
+@allow_blank_line_before)
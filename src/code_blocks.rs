@@ -0,0 +1,293 @@
+//! This module formats the GDScript code blocks found in documentation files:
+//! ```gdscript fences in Markdown files and `[codeblock]` sections in the XML
+//! class reference of Godot projects and modules (`doc_classes/*.xml`).
+//!
+//! We only rewrite the content of code blocks. Each block keeps the
+//! indentation it has in the document, and we leave blocks with syntax errors
+//! as they are and report them.
+use std::{ops::Range, path::Path};
+
+use tree_sitter::{Node, Parser};
+
+use crate::FormatterConfig;
+use crate::formatter::format_gdscript_with_config;
+
+/// The kinds of documents that can contain GDScript code blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DocumentKind {
+    /// Markdown files, with ```gdscript fenced code blocks
+    Markdown,
+    /// Godot class reference XML files, with BBCode `[codeblock]` and
+    /// `[gdscript]` sections
+    ClassReference,
+}
+
+impl DocumentKind {
+    /// Returns the kind of document based on the file extension, or None if
+    /// the file isn't a document we can format code blocks in.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "md" => Some(Self::Markdown),
+            "xml" => Some(Self::ClassReference),
+            _ => None,
+        }
+    }
+}
+
+/// A code block we couldn't format, usually because of a syntax error.
+#[derive(Debug, Clone)]
+pub struct CodeBlockError {
+    /// Line of the document where the error is, starting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+/// The result of formatting the code blocks of a document.
+pub struct CodeBlocksOutput {
+    /// The document with its code blocks formatted.
+    pub content: String,
+    /// The code blocks that we left unchanged because we couldn't format them.
+    pub errors: Vec<CodeBlockError>,
+}
+
+/// A GDScript code block in a document.
+struct CodeBlock {
+    /// Byte range of the lines of code, between the opening and closing lines.
+    range: Range<usize>,
+    /// Indentation of the opening line, which the lines of code share.
+    indent: String,
+    /// Line of the document where the code starts, starting from 1.
+    line: usize,
+}
+
+/// Formats every GDScript code block of a document and returns the new
+/// document content along with the blocks we couldn't format.
+pub fn format_code_blocks_with_config(
+    content: &str,
+    kind: DocumentKind,
+    config: &FormatterConfig,
+) -> CodeBlocksOutput {
+    let blocks = match kind {
+        DocumentKind::Markdown => find_markdown_code_blocks(content),
+        DocumentKind::ClassReference => find_bbcode_code_blocks(content),
+    };
+
+    let mut output = String::with_capacity(content.len());
+    let mut errors = Vec::new();
+    let mut last_end = 0;
+    for block in blocks {
+        output.push_str(&content[last_end..block.range.start]);
+        match format_code_block(&content[block.range.clone()], &block, kind, config) {
+            Ok(Some(formatted)) => output.push_str(&formatted),
+            Ok(None) => output.push_str(&content[block.range.clone()]),
+            Err(error) => {
+                output.push_str(&content[block.range.clone()]);
+                errors.push(error);
+            }
+        }
+        last_end = block.range.end;
+    }
+    output.push_str(&content[last_end..]);
+
+    CodeBlocksOutput {
+        content: output,
+        errors,
+    }
+}
+
+/// Formats the code of one block. Returns None if the code is already
+/// formatted.
+fn format_code_block(
+    code: &str,
+    block: &CodeBlock,
+    kind: DocumentKind,
+    config: &FormatterConfig,
+) -> Result<Option<String>, CodeBlockError> {
+    let mut source = String::with_capacity(code.len());
+    for (index, line) in code.lines().enumerate() {
+        if let Some(dedented) = line.strip_prefix(block.indent.as_str()) {
+            source.push_str(dedented);
+        } else if !line.trim().is_empty() {
+            return Err(CodeBlockError {
+                line: block.line + index,
+                message: "the line is less indented than the code block".to_string(),
+            });
+        }
+        source.push('\n');
+    }
+    if kind == DocumentKind::ClassReference {
+        source = unescape_xml(&source);
+    }
+    if source.trim().is_empty() {
+        return Ok(None);
+    }
+
+    // The formatter tolerates syntax errors, so we check for them first to
+    // leave invalid code blocks untouched
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_gdscript::LANGUAGE.into())
+        .unwrap();
+    let tree = parser.parse(&source, None).unwrap();
+    if let Some(error_node) = find_first_error(tree.root_node()) {
+        return Err(CodeBlockError {
+            line: block.line + error_node.start_position().row,
+            message: "syntax error in GDScript code block".to_string(),
+        });
+    }
+
+    let formatted =
+        format_gdscript_with_config(&source, config).map_err(|error| CodeBlockError {
+            line: block.line,
+            message: error.to_string(),
+        })?;
+    if formatted == source {
+        return Ok(None);
+    }
+
+    let formatted = match kind {
+        DocumentKind::Markdown => formatted,
+        DocumentKind::ClassReference => escape_xml(&formatted),
+    };
+    let mut reindented = String::with_capacity(formatted.len());
+    for line in formatted.lines() {
+        if !line.is_empty() {
+            reindented.push_str(&block.indent);
+            reindented.push_str(line);
+        }
+        reindented.push('\n');
+    }
+    Ok(Some(reindented))
+}
+
+/// Returns the ```gdscript and ~~~gdscript fenced code blocks of a Markdown
+/// document.
+fn find_markdown_code_blocks(content: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    // The opening fence of the code block we're in, if any, and whether the
+    // block contains GDScript
+    let mut open_fence: Option<(&str, bool, CodeBlock)> = None;
+
+    for (line_index, (line_start, line)) in lines_with_offsets(content).enumerate() {
+        let trimmed = line.trim_start_matches([' ', '\t']);
+        let indent = &line[..line.len() - trimmed.len()];
+        let line_end = line_start + line.len();
+
+        match open_fence.take() {
+            None => {
+                let fence_length = trimmed
+                    .find(|c| c != '`' && c != '~')
+                    .unwrap_or(trimmed.len());
+                let fence = &trimmed[..fence_length];
+                let is_fence = fence.len() >= 3
+                    && (fence.chars().all(|c| c == '`') || fence.chars().all(|c| c == '~'));
+                if !is_fence {
+                    continue;
+                }
+                let info = trimmed[fence_length..].trim();
+                let is_gdscript = info == "gdscript" || info == "gd";
+                let block = CodeBlock {
+                    range: line_end..line_end,
+                    indent: indent.to_string(),
+                    line: line_index + 2,
+                };
+                open_fence = Some((fence, is_gdscript, block));
+            }
+            Some((fence, is_gdscript, mut block)) => {
+                // A closing fence has at least as many characters as the
+                // opening one, and nothing else
+                let closing = trimmed.trim_end();
+                let is_closing_fence =
+                    closing.starts_with(fence) && closing.chars().all(|c| fence.starts_with(c));
+                if !is_closing_fence {
+                    open_fence = Some((fence, is_gdscript, block));
+                    continue;
+                }
+                if is_gdscript {
+                    block.range.end = line_start;
+                    blocks.push(block);
+                }
+            }
+        }
+    }
+
+    blocks
+}
+
+/// Returns the `[codeblock]` and `[gdscript]` sections of a Godot class
+/// reference document. The opening and closing tags must be on their own
+/// lines.
+fn find_bbcode_code_blocks(content: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut open_block: Option<(&str, CodeBlock)> = None;
+
+    for (line_index, (line_start, line)) in lines_with_offsets(content).enumerate() {
+        let trimmed = line.trim();
+        match open_block.take() {
+            None => {
+                let closing_tag = match trimmed {
+                    "[codeblock]" | "[codeblock lang=gdscript]" => "[/codeblock]",
+                    "[gdscript]" => "[/gdscript]",
+                    _ => continue,
+                };
+                let indent_length = line.len() - line.trim_start().len();
+                let block = CodeBlock {
+                    range: line_start + line.len()..line_start + line.len(),
+                    indent: line[..indent_length].to_string(),
+                    line: line_index + 2,
+                };
+                open_block = Some((closing_tag, block));
+            }
+            Some((closing_tag, mut block)) => {
+                if trimmed != closing_tag {
+                    open_block = Some((closing_tag, block));
+                    continue;
+                }
+                block.range.end = line_start;
+                blocks.push(block);
+            }
+        }
+    }
+
+    blocks
+}
+
+/// Returns each line of the content with its byte offset, including the line
+/// ending.
+fn lines_with_offsets(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content.split_inclusive('\n').scan(0, |offset, line| {
+        let line_start = *offset;
+        *offset += line.len();
+        Some((line_start, line))
+    })
+}
+
+/// Returns the first node with a syntax error in document order, if any.
+fn find_first_error(node: Node) -> Option<Node> {
+    if !node.has_error() {
+        return None;
+    }
+    if node.is_error() || node.is_missing() {
+        return Some(node);
+    }
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.children(&mut cursor).collect();
+    children
+        .into_iter()
+        .find_map(find_first_error)
+        .or(Some(node))
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
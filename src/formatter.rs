@@ -16,11 +16,14 @@ use std::{collections::VecDeque, io::BufWriter};
 
 use regex::{Regex, RegexBuilder, Replacer};
 use topiary_core::{Language, Operation, TopiaryQuery, formatter_tree};
-use tree_sitter::{Parser, Point, Query, QueryCursor, StreamingIterator, Tree};
+use tree_sitter::{Node, Parser, Point, Query, QueryCursor, StreamingIterator, Tree};
 
-use crate::FormatterConfig;
+use crate::{AnnotationPlacement, FormatterConfig, GodotVersion, HexDigitCase, TrailingCommas};
 
 static QUERY: &str = include_str!("../queries/gdscript.scm");
+/// Extra rules for Godot 3 syntax, appended to the main query when formatting
+/// Godot 3 scripts.
+static QUERY_GODOT_3: &str = include_str!("../queries/gdscript_godot_3.scm");
 
 pub fn format_gdscript(content: &str) -> Result<String, Box<dyn std::error::Error>> {
     format_gdscript_with_config(content, &FormatterConfig::default())
@@ -42,6 +45,15 @@ struct Formatter {
     parser: Parser,
     input_tree: GdTree,
     tree: Tree,
+    /// Blank lines above each statement in the input, only collected when the
+    /// config allows more consecutive blank lines than Topiary preserves.
+    original_blank_lines: Option<Vec<(u16, usize)>>,
+    /// Whether each comma-separated list in the input has a trailing comma,
+    /// only collected when the config asks to preserve trailing commas.
+    original_trailing_commas: Option<Vec<(u16, bool)>>,
+    /// Whether the user wrapped each method chain of the input on multiple
+    /// lines.
+    original_wrapped_chains: Vec<bool>,
 }
 
 impl Formatter {
@@ -52,7 +64,13 @@ impl Formatter {
             .set_language(&tree_sitter_gdscript::LANGUAGE.into())
             .unwrap();
         let tree = parser.parse(&content, None).unwrap();
-        let input_tree = GdTree::from_ts_tree(&tree, content.as_bytes());
+        let input_tree = GdTree::from_ts_tree(&tree, content.as_bytes(), config.reflow_comments);
+        let original_blank_lines = (config.max_blank_lines_in_functions > 1
+            || config.max_blank_lines_between_properties > 1)
+            .then(|| collect_blank_lines(&tree, &content));
+        let original_trailing_commas = (config.trailing_commas == TrailingCommas::Preserve)
+            .then(|| collect_trailing_commas(&tree));
+        let original_wrapped_chains = collect_wrapped_chains(&tree);
 
         Self {
             content,
@@ -60,20 +78,28 @@ impl Formatter {
             tree,
             input_tree,
             parser,
+            original_blank_lines,
+            original_trailing_commas,
+            original_wrapped_chains,
         }
     }
 
     #[inline(always)]
     fn format(&mut self) -> Result<&mut Self, Box<dyn std::error::Error>> {
-        let indent_string = if self.config.use_spaces {
-            " ".repeat(self.config.indent_size)
-        } else {
-            "\t".to_string()
+        let indent_string = self.indent_string();
+
+        let query = match self.config.godot_version {
+            GodotVersion::Godot3 => TopiaryQuery::new(
+                &tree_sitter_gdscript::LANGUAGE.into(),
+                &format!("{}\n{}", QUERY, QUERY_GODOT_3),
+            ),
+            GodotVersion::Godot4 => {
+                TopiaryQuery::new(&tree_sitter_gdscript::LANGUAGE.into(), QUERY)
+            }
         };
-
         let language = Language {
             name: "gdscript".to_owned(),
-            query: TopiaryQuery::new(&tree_sitter_gdscript::LANGUAGE.into(), QUERY).unwrap(),
+            query: query.unwrap(),
             grammar: tree_sitter_gdscript::LANGUAGE.into(),
             indent: Some(indent_string),
         };
@@ -101,6 +127,15 @@ impl Formatter {
         Ok(self)
     }
 
+    /// Returns the string for one level of indentation.
+    fn indent_string(&self) -> String {
+        if self.config.use_spaces {
+            " ".repeat(self.config.indent_size)
+        } else {
+            "\t".to_string()
+        }
+    }
+
     #[inline(always)]
     fn reorder(&mut self) -> &mut Self {
         if !self.config.reorder_code {
@@ -108,7 +143,7 @@ impl Formatter {
         }
 
         self.tree = self.parser.parse(&self.content, Some(&self.tree)).unwrap();
-        match crate::reorder::reorder_gdscript_elements(&self.tree, &self.content) {
+        match crate::reorder::reorder_gdscript_elements(&self.tree, &self.content, &self.config) {
             Ok(reordered) => {
                 self.content = reordered;
             }
@@ -137,7 +172,6 @@ impl Formatter {
             .fix_dangling_semicolons()
             .fix_dangling_commas()
             .fix_trailing_spaces()
-            .remove_trailing_commas_from_preload()
             .postprocess_tree_sitter()
     }
 
@@ -148,7 +182,12 @@ impl Formatter {
             self.input_tree.postprocess();
             self.tree = self.parser.parse(&self.content, None).unwrap();
 
-            let output_tree = GdTree::from_ts_tree(&self.tree, self.content.as_bytes());
+            let mut output_tree = GdTree::from_ts_tree(
+                &self.tree,
+                self.content.as_bytes(),
+                self.config.reflow_comments,
+            );
+            output_tree.postprocess();
             if self.input_tree != output_tree {
                 return Err("Code structure has changed after formatting".into());
             }
@@ -157,7 +196,8 @@ impl Formatter {
         Ok(self.content)
     }
 
-    /// This function adds additional new line characters after `extends_statement`.
+    /// This function adds blank lines after `extends_statement`, as many as
+    /// `blank_lines_after_extends` in the config.
     #[inline(always)]
     fn add_newlines_after_extends_statement(&mut self) -> &mut Self {
         // This regex matches substrings which:
@@ -174,12 +214,17 @@ impl Formatter {
         .build()
         .expect("regex should compile");
 
+        let blank_lines_after_extends = self.config.blank_lines_after_extends;
         self.regex_replace_all_outside_strings(re, |caps: &regex::Captures| {
             let extends_line = caps.name("extends_line").unwrap().as_str();
             let extends_name = caps.name("extends_name").unwrap().as_str();
             let doc = caps.name("doc").map(|m| m.as_str()).unwrap_or_default();
-            // insert new line only if we are not at the end of file
-            let blank_new_line = if caps.name("EOF").is_some() { "" } else { "\n" };
+            // insert new lines only if we are not at the end of file
+            let blank_new_line = if caps.name("EOF").is_some() {
+                String::new()
+            } else {
+                "\n".repeat(blank_lines_after_extends)
+            };
 
             format!(
                 "{}{}\n{}{}",
@@ -235,25 +280,18 @@ impl Formatter {
         self
     }
 
-    /// This function removes trailing commas from preload function calls.
-    /// The GDScript parser doesn't support trailing commas in preload calls,
-    /// but our formatter might add them for multi-line calls.
-    #[inline(always)]
-    fn remove_trailing_commas_from_preload(&mut self) -> &mut Self {
-        let re = RegexBuilder::new(r"preload\s*\(([^)]*),(\s*)\)")
-            .build()
-            .expect("preload regex should compile");
-
-        self.regex_replace_all_outside_strings(re, "preload($1$2)");
-        self
-    }
-
     /// This function runs postprocess passes that uses tree-sitter.
     #[inline(always)]
     fn postprocess_tree_sitter(&mut self) -> &mut Self {
         self.tree = self.parser.parse(&self.content, None).unwrap();
 
-        self.handle_two_blank_line()
+        self.normalize_number_literals()
+            .apply_trailing_comma_policy()
+            .limit_blank_lines()
+            .handle_blank_lines_around_definitions()
+            .place_annotations()
+            .wrap_method_chains()
+            .format_comments()
     }
 
     /// Replaces every match of regex `re` with `rep`, but only if the match is
@@ -261,26 +299,13 @@ impl Formatter {
     /// Use this to make post-processing changes needed for formatting but that
     /// shouldn't affect strings in the source code.
     fn regex_replace_all_outside_strings<R: Replacer>(&mut self, re: Regex, mut rep: R) {
-        let mut iter = re.captures_iter(&self.content).peekable();
-        if iter.peek().is_none() {
-            return;
-        }
-
-        let mut new = String::new();
-        let mut last_match = 0;
-        let mut start_position = Point::new(0, 0);
-
-        // We first collect tree edits and then apply them, because regex returns positions from unmodified content
-        let mut edits = Vec::new();
-
-        for capture in iter {
+        let mut replacements = Vec::new();
+        for capture in re.captures_iter(&self.content) {
             let m = capture.get(0).unwrap();
-            let start_byte = m.start();
-            let old_end_byte = m.end();
             let node = self
                 .tree
                 .root_node()
-                .descendant_for_byte_range(start_byte, start_byte)
+                .descendant_for_byte_range(m.start(), m.start())
                 .unwrap();
             if node.kind() == "string" {
                 continue;
@@ -288,7 +313,31 @@ impl Formatter {
 
             let mut replacement = String::new();
             rep.replace_append(&capture, &mut replacement);
+            replacements.push((m.start(), m.end(), replacement));
+        }
+
+        self.replace_byte_ranges(replacements);
+    }
+
+    /// Replaces each `(start_byte, end_byte, replacement)` range of the content
+    /// and updates the tree-sitter tree to match. The ranges must be sorted and
+    /// must not overlap, and they refer to positions in the unmodified content.
+    /// Use this for tree-driven edits: collect nodes to rewrite, then apply all
+    /// the changes in one go.
+    fn replace_byte_ranges(&mut self, replacements: Vec<(usize, usize, String)>) {
+        if replacements.is_empty() {
+            return;
+        }
+
+        let mut new = String::new();
+        let mut last_match = 0;
+        let mut start_position = Point::new(0, 0);
+
+        // We first collect tree edits and then apply them, because the ranges
+        // are positions in the unmodified content
+        let mut edits = Vec::new();
 
+        for (start_byte, old_end_byte, replacement) in replacements {
             let new_end_byte = start_byte + replacement.len();
 
             let slice = &self.content[last_match..start_byte];
@@ -315,18 +364,357 @@ impl Formatter {
         new.push_str(&self.content[last_match..]);
         self.content = new;
 
-        for edit in edits {
-            self.tree.edit(&edit);
+        // We apply the edits from last to first so that each edit's positions
+        // are still valid in the tree when we apply it
+        for edit in edits.iter().rev() {
+            self.tree.edit(edit);
         }
         self.tree = self.parser.parse(&self.content, Some(&self.tree)).unwrap();
     }
 
+    /// This function rewrites integer and float literals to a canonical form:
+    /// lowercase `0x` and `0b` prefixes and exponents, configurable hexadecimal
+    /// digit case, explicit zeros around the decimal point of floats, and
+    /// optionally underscores between groups of thousands.
+    ///
+    /// We query the number nodes from the syntax tree rather than using a regex
+    /// so that we never touch numbers inside strings, comments, or identifiers.
+    fn normalize_number_literals(&mut self) -> &mut Self {
+        if !self.config.normalize_numbers {
+            return self;
+        }
+
+        let query = Query::new(
+            &tree_sitter_gdscript::LANGUAGE.into(),
+            "[(integer) (float)] @number",
+        )
+        .expect("number literal query should compile");
+
+        let mut replacements = Vec::new();
+        {
+            let mut cursor = QueryCursor::new();
+            let mut matches =
+                cursor.matches(&query, self.tree.root_node(), self.content.as_bytes());
+            while let Some(m) = matches.next() {
+                let node = m.captures[0].node;
+                let literal = &self.content[node.byte_range()];
+                let normalized = normalize_number_literal(
+                    literal,
+                    self.config.hex_digit_case,
+                    self.config.group_digits,
+                );
+                if normalized != literal {
+                    replacements.push((node.start_byte(), node.end_byte(), normalized));
+                }
+            }
+        }
+
+        self.replace_byte_ranges(replacements);
+        self
+    }
+
+    /// This function adds or removes the comma after the last element of
+    /// multi-line arrays, dictionaries, enums, arguments, and parameters,
+    /// following the `trailing_commas` option.
+    ///
+    /// The GDScript parser doesn't support trailing commas in preload calls,
+    /// so we always remove them there, whatever the option.
+    fn apply_trailing_comma_policy(&mut self) -> &mut Self {
+        let original_trailing_commas = self.original_trailing_commas.take();
+
+        let mut replacements = Vec::new();
+        {
+            let lists = collect_comma_separated_lists(&self.tree);
+            // We can only match input and output lists if formatting
+            // preserved them all, which should be the case unless the formatter
+            // made a mistake
+            let original_trailing_commas = original_trailing_commas.filter(|original| {
+                original.len() == lists.len()
+                    && original
+                        .iter()
+                        .zip(&lists)
+                        .all(|((kind_id, _), node)| *kind_id == node.kind_id())
+            });
+
+            for (index, list) in lists.iter().enumerate() {
+                // Godot 3 doesn't allow trailing commas in calls and function
+                // parameters
+                let forbids_trailing_comma = is_preload_arguments(*list, &self.content)
+                    || (self.config.godot_version == GodotVersion::Godot3
+                        && matches!(list.kind(), "arguments" | "parameters"));
+                let is_multi_line = list.start_position().row != list.end_position().row;
+                if !is_multi_line && !forbids_trailing_comma {
+                    continue;
+                }
+                let Some((last_element, trailing_comma)) = find_trailing_comma(*list) else {
+                    continue;
+                };
+
+                let wants_trailing_comma = if forbids_trailing_comma {
+                    false
+                } else {
+                    match self.config.trailing_commas {
+                        TrailingCommas::Always => true,
+                        TrailingCommas::Never => false,
+                        TrailingCommas::Preserve => match &original_trailing_commas {
+                            Some(original) => original[index].1,
+                            None => continue,
+                        },
+                    }
+                };
+
+                match trailing_comma {
+                    Some(comma) if !wants_trailing_comma => {
+                        replacements.push((comma.start_byte(), comma.end_byte(), String::new()));
+                    }
+                    None if wants_trailing_comma => {
+                        let end = last_element.end_byte();
+                        replacements.push((end, end, ",".to_string()));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Lists can be nested, so we need to sort the edits
+        replacements.sort_by_key(|(start, _, _)| *start);
+        self.replace_byte_ranges(replacements);
+        self
+    }
+
+    /// This function moves the annotations of variables and functions onto
+    /// the line of the declaration or onto their own lines, following the
+    /// `variable_annotations` and `function_annotations` options.
+    ///
+    /// Annotations that apply to the whole script or to a group of properties,
+    /// like `@tool` or `@export_group`, always stay on their own line.
+    fn place_annotations(&mut self) -> &mut Self {
+        let variable_annotations = self.config.variable_annotations;
+        let function_annotations = self.config.function_annotations;
+        if variable_annotations == AnnotationPlacement::Preserve
+            && function_annotations == AnnotationPlacement::Preserve
+        {
+            return self;
+        }
+
+        let mut replacements = Vec::new();
+        {
+            let declarations = collect_nodes_of_kinds(
+                self.tree.root_node(),
+                &["variable_statement", "function_definition"],
+            );
+            for declaration in declarations {
+                let placement = if declaration.kind() == "variable_statement" {
+                    variable_annotations
+                } else {
+                    function_annotations
+                };
+                let replacement = match placement {
+                    AnnotationPlacement::Inline => inline_short_annotations(
+                        declaration,
+                        &self.content,
+                        self.config.line_width,
+                        self.config.indent_size,
+                    ),
+                    AnnotationPlacement::OwnLine => {
+                        move_annotations_to_own_lines(declaration, &self.content)
+                    }
+                    AnnotationPlacement::Preserve => None,
+                };
+                replacements.extend(replacement);
+            }
+        }
+
+        self.replace_byte_ranges(replacements);
+        self
+    }
+
+    /// This function wraps method chains like
+    /// `create_tween().set_trans(...).set_ease(...)` with one call per line,
+    /// when they make their line longer than `line_width` or when the user
+    /// already wrapped them in the input.
+    ///
+    /// Inside parentheses, brackets, and braces, GDScript allows line breaks
+    /// anywhere, so we break lines directly. Elsewhere, we need a backslash at
+    /// the end of each wrapped line.
+    fn wrap_method_chains(&mut self) -> &mut Self {
+        let original_wrapped_chains = std::mem::take(&mut self.original_wrapped_chains);
+        let indent_string = self.indent_string();
+
+        let mut replacements = Vec::new();
+        {
+            let chains = collect_method_chains(&self.tree);
+            // As with other input information, we can only match input and
+            // output chains if formatting preserved them all
+            let original_wrapped_chains =
+                (original_wrapped_chains.len() == chains.len()).then_some(original_wrapped_chains);
+
+            // We don't wrap chains nested in the arguments of a chain we
+            // already wrapped, as the edits would overlap
+            let mut wrapped_end_byte = 0;
+            for (index, chain) in chains.iter().enumerate() {
+                if chain.start_byte() < wrapped_end_byte {
+                    continue;
+                }
+                let was_wrapped = original_wrapped_chains
+                    .as_ref()
+                    .is_some_and(|original| original[index]);
+                if !was_wrapped
+                    && measure_line_width(
+                        &self.content,
+                        chain.start_byte(),
+                        self.config.indent_size,
+                    ) <= self.config.line_width
+                {
+                    continue;
+                }
+                let Some(chain_replacements) =
+                    wrap_method_chain(*chain, &self.content, &indent_string)
+                else {
+                    continue;
+                };
+                replacements.extend(chain_replacements);
+                wrapped_end_byte = chain.end_byte();
+            }
+        }
+
+        self.replace_byte_ranges(replacements);
+        self
+    }
+
+    /// This function adds a space after the `#` of comments, and reflows the
+    /// paragraphs of consecutive `##` doc comments to fit in `line_width`.
+    ///
+    /// We leave `#!` lines, regions, and `gdlint` and `fmt` directives as they
+    /// are. In doc comments, BBCode code blocks and tags like `@tutorial` keep
+    /// their own lines.
+    fn format_comments(&mut self) -> &mut Self {
+        if !self.config.reflow_comments {
+            return self;
+        }
+
+        let mut replacements = Vec::new();
+        {
+            let comments = collect_nodes_of_kinds(self.tree.root_node(), &["comment"]);
+            let mut index = 0;
+            while index < comments.len() {
+                let comment = comments[index];
+                let text = &self.content[comment.byte_range()];
+                // Inline doc comments, after code, only get the space
+                let indent = find_line_indent(&self.content, comment);
+                let Some(indent) = indent.filter(|_| is_doc_comment(text)) else {
+                    if let Some(spaced) = add_space_after_hash(text) {
+                        replacements.push((comment.start_byte(), comment.end_byte(), spaced));
+                    }
+                    index += 1;
+                    continue;
+                };
+
+                // We reflow consecutive doc comment lines at the same
+                // indentation as one block
+                let mut end = index + 1;
+                while let Some(next) = comments.get(end) {
+                    let is_next_line =
+                        next.start_position().row == comments[end - 1].start_position().row + 1;
+                    if !is_next_line
+                        || !is_doc_comment(&self.content[next.byte_range()])
+                        || find_line_indent(&self.content, *next) != Some(indent)
+                    {
+                        break;
+                    }
+                    end += 1;
+                }
+
+                let block = &comments[index..end];
+                let lines: Vec<&str> = block
+                    .iter()
+                    .map(|comment| &self.content[comment.byte_range()])
+                    .collect();
+                let available_width = self
+                    .config
+                    .line_width
+                    .saturating_sub(measure_width(indent, self.config.indent_size) + "## ".len());
+                let reflowed =
+                    reflow_doc_comment(&lines, available_width).join(&format!("\n{indent}"));
+                let start_byte = block[0].start_byte();
+                let end_byte = block[block.len() - 1].end_byte();
+                if self.content[start_byte..end_byte] != reflowed {
+                    replacements.push((start_byte, end_byte, reflowed));
+                }
+                index = end;
+            }
+        }
+
+        self.replace_byte_ranges(replacements);
+        self
+    }
+
+    /// This function applies the maximum number of consecutive blank lines
+    /// allowed between statements in function bodies and between class members
+    /// that aren't methods or inner classes (variables, constants, signals...).
+    ///
+    /// Topiary keeps at most one blank line where users put some, so if the
+    /// config allows more, we restore the blank lines from the input code, up
+    /// to the maximum. If the config allows none, we remove them.
+    fn limit_blank_lines(&mut self) -> &mut Self {
+        let original_blank_lines = self.original_blank_lines.take();
+
+        let mut replacements = Vec::new();
+        {
+            let nodes = collect_spaced_nodes(&self.tree);
+            // We can only match input and output statements if formatting
+            // preserved the sequence of statements, which should be the case
+            // unless the formatter made a mistake
+            let original_blank_lines = original_blank_lines.filter(|original| {
+                original.len() == nodes.len()
+                    && original
+                        .iter()
+                        .zip(&nodes)
+                        .all(|((kind_id, _), node)| *kind_id == node.kind_id())
+            });
+
+            for (index, node) in nodes.iter().enumerate() {
+                let Some(parent) = node.parent() else {
+                    continue;
+                };
+                // Topiary removes blank lines at the start of blocks
+                if node.prev_named_sibling().is_none() {
+                    continue;
+                }
+                let max_blank_lines = if parent.kind() == "body" {
+                    self.config.max_blank_lines_in_functions
+                } else if is_spaced_as_property(*node) {
+                    self.config.max_blank_lines_between_properties
+                } else {
+                    continue;
+                };
+                let Some((run_start, line_start)) = find_blank_lines_above(&self.content, *node)
+                else {
+                    continue;
+                };
+
+                let current = count_lines(&self.content[run_start..line_start]);
+                let original = original_blank_lines
+                    .as_ref()
+                    .map_or(current, |original| original[index].1);
+                let wanted = current.max(original).min(max_blank_lines);
+                if wanted != current {
+                    replacements.push((run_start, line_start, "\n".repeat(wanted)));
+                }
+            }
+        }
+
+        self.replace_byte_ranges(replacements);
+        self
+    }
+
     /// This function makes sure we have the correct vertical spacing between important definitions:
-    /// Two blank lines between function definitions, inner classes, etc. Taking any
-    /// comments or docstrings into account.
+    /// By default, two blank lines between function definitions, inner classes, etc. Taking any
+    /// comments or docstrings into account. The number of blank lines comes from
+    /// `blank_lines_between_methods` and `blank_lines_between_classes` in the config.
     ///
     /// This uses tree-sitter to find the relevant nodes and their positions.
-    fn handle_two_blank_line(&mut self) -> &mut Self {
+    fn handle_blank_lines_around_definitions(&mut self) -> &mut Self {
         let root = self.tree.root_node();
         let queries = [
             // We need two queries to catch all cases because variables can be placed above or below functions
@@ -344,7 +732,7 @@ impl Formatter {
         ];
 
         let process_query =
-            |query_str: &str, new_lines_at: &mut Vec<(usize, tree_sitter::Point)>| {
+            |query_str: &str, blank_lines_before_definitions: &mut Vec<(usize, usize)>| {
                 let query = match Query::new(
                     &tree_sitter::Language::new(tree_sitter_gdscript::LANGUAGE),
                     query_str,
@@ -402,13 +790,22 @@ impl Formatter {
                         }
                     }
 
+                    // Inner classes use their own spacing, both before and after them
+                    let blank_lines = if first_node.kind() == "class_definition"
+                        || last_node.kind() == "class_definition"
+                    {
+                        self.config.blank_lines_between_classes
+                    } else {
+                        self.config.blank_lines_between_methods
+                    };
+
                     let mut byte_idx = insert_before.start_byte();
-                    let mut position = insert_before.start_position();
-                    position.column = 0;
                     while byte_idx > 0 && self.content.as_bytes()[byte_idx] != b'\n' {
                         byte_idx -= 1;
                     }
-                    new_lines_at.push((byte_idx, position));
+                    if self.content.as_bytes()[byte_idx] == b'\n' {
+                        blank_lines_before_definitions.push((byte_idx, blank_lines));
+                    }
                 }
             };
 
@@ -421,37 +818,28 @@ impl Formatter {
             process_query(query_str, &mut new_lines_at);
         }
 
-        // We sort the positions in reverse order so that when we insert new lines,
-        // we don't mess up the positions of the other insertions we need to make.
-        new_lines_at.sort_by(|a, b| b.cmp(a));
+        // If two matches target the same line, the larger spacing wins
+        new_lines_at.sort_unstable_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        new_lines_at.dedup_by_key(|(byte_idx, _)| *byte_idx);
+
+        // Each position is the line break that ends the line above the
+        // definition. We replace it and the blank lines before it with exactly
+        // the number of line breaks we want.
+        let content = self.content.as_bytes();
+        let replacements = new_lines_at
+            .into_iter()
+            .filter_map(|(byte_idx, blank_lines)| {
+                let mut run_start = byte_idx;
+                while run_start > 0 && content[run_start - 1] == b'\n' {
+                    run_start -= 1;
+                }
+                let existing_blank_lines = byte_idx - run_start;
+                (existing_blank_lines != blank_lines)
+                    .then(|| (run_start, byte_idx + 1, "\n".repeat(blank_lines + 1)))
+            })
+            .collect();
 
-        for (byte_idx, position) in new_lines_at {
-            let mut new_end_position = position;
-            let mut new_end_byte_idx = byte_idx;
-            // Only add a second blank line if there isn't already one
-            if !(self.content.as_bytes()[byte_idx] == b'\n'
-                && self.content.as_bytes()[byte_idx - 1] == b'\n')
-            {
-                new_end_position.row += 1;
-                new_end_byte_idx += 1;
-                self.content.insert(byte_idx, '\n');
-            }
-            // Add the first blank line
-            new_end_position.row += 1;
-            new_end_byte_idx += 1;
-            self.content.insert(byte_idx, '\n');
-
-            // Update the tree sitter parse tree to reflect our changes so that any
-            // future processing will work with the correct positions
-            self.tree.edit(&tree_sitter::InputEdit {
-                start_byte: byte_idx,
-                old_end_byte: byte_idx,
-                new_end_byte: new_end_byte_idx,
-                start_position: position,
-                old_end_position: position,
-                new_end_position,
-            });
-        }
+        self.replace_byte_ranges(replacements);
         self
     }
 }
@@ -463,7 +851,11 @@ struct GdTree {
 
 impl GdTree {
     /// Constructs a new `GdTree` from `TSTree`.
-    fn from_ts_tree(tree: &Tree, source: &[u8]) -> Self {
+    ///
+    /// When `merge_comments` is true, consecutive comments count as one node
+    /// whose text is the normalized text of all the comments, as reflowing
+    /// comments changes the number of comment lines.
+    fn from_ts_tree(tree: &Tree, source: &[u8], merge_comments: bool) -> Self {
         let mut cursor = tree.walk();
         let mut nodes = Vec::new();
 
@@ -484,14 +876,15 @@ impl GdTree {
         while let Some((parent_ts_node, parent_node_id)) = queue.pop_front() {
             let ts_children = parent_ts_node.children(&mut cursor);
             for ts_child in ts_children {
-                // Skip anonymous nodes
-                if !ts_child.is_named() {
+                // Skip anonymous nodes and line continuations, as the formatter
+                // adds and removes backslashes when wrapping lines
+                if !ts_child.is_named() || ts_child.kind() == "line_continuation" {
                     continue;
                 }
 
                 // Get node's text in the source code (e.g. variable's name)
                 // None if this node is not a leaf node
-                let text = if ts_child.child(0).is_none() {
+                let mut text = if ts_child.child(0).is_none() {
                     let range = ts_child.range();
                     Some(
                         str::from_utf8(&source[range.start_byte..range.end_byte])
@@ -502,6 +895,32 @@ impl GdTree {
                     None
                 };
 
+                if ts_child.kind() == "comment"
+                    && let Some(comment) = text.take()
+                {
+                    let previous_comment = nodes[parent_node_id]
+                        .children
+                        .last()
+                        .copied()
+                        .filter(|&id| merge_comments && nodes[id].grammar_name == "comment");
+                    if let Some(previous_id) = previous_comment {
+                        let merged_text = nodes[previous_id].text.get_or_insert_default();
+                        push_normalized_comment(merged_text, &comment);
+                        continue;
+                    }
+                    let mut normalized = String::new();
+                    push_normalized_comment(&mut normalized, &comment);
+                    text = Some(normalized);
+                }
+
+                // Normalizing number literals changes how they're written but
+                // not their value
+                if matches!(ts_child.kind(), "integer" | "float")
+                    && let Some(literal) = &mut text
+                {
+                    *literal = normalize_number_for_comparison(literal);
+                }
+
                 let child_id = nodes.len();
                 let child = GdTreeNode {
                     parent_id: Some(parent_node_id),
@@ -566,6 +985,12 @@ impl GdTree {
         }
     }
 
+    /// Moves the annotations on the lines above variables and functions into
+    /// the declaration's `annotations` node, as if they were on the same line.
+    ///
+    /// The formatter moves annotations onto the declaration's line or onto
+    /// their own lines depending on the config, which changes the syntax tree,
+    /// so we apply this to both the input and output trees.
     fn move_annotations(&mut self) {
         let language: &tree_sitter::Language = &tree_sitter_gdscript::LANGUAGE.into();
         let annotations_grammar_id = language.id_for_node_kind("annotations", true);
@@ -582,74 +1007,78 @@ impl GdTree {
                 let child_grammar_name = self.nodes[child_id].grammar_name;
 
                 // We do the same in inner classes
-                if child_grammar_name == "class_definition" {
+                if matches!(child_grammar_name, "class_definition" | "class_body") {
                     stack.push(child_id);
                     continue;
                 }
 
-                if child_grammar_name == "variable_statement" {
-                    // We move @onready and @export annotations on the same line as the variable after formatting,
-                    // that means we need to move these annotations to be children of the variable_statement node
-                    // We move from the current index back to 0, searching for any annotations
-                    let annotations_to_move = (0..index)
-                        .rev()
-                        .map_while(|i| {
-                            let child_id = self.nodes[parent_id].children[i];
-                            let child = &self.nodes[child_id];
-                            if child.grammar_name != "annotation" {
-                                return None;
-                            }
-                            let Some(annotation_name) = &self.nodes[child.children[0]].text else {
-                                return None;
-                            };
-                            if annotation_name != "onready" && annotation_name != "export" {
-                                return None;
-                            }
-                            let parent = &mut self.nodes[parent_id];
-                            // When we found one, we remove it from the parent and collect them in a vector
-                            let annotation_id = parent.children.remove(i);
-                            index -= 1;
-                            Some(annotation_id)
-                        })
-                        .collect::<Vec<_>>();
-
-                    if annotations_to_move.is_empty() {
-                        continue;
-                    }
+                if !matches!(
+                    child_grammar_name,
+                    "variable_statement" | "function_definition"
+                ) {
+                    continue;
+                }
 
-                    let mut annotations_node_exists = false;
-
-                    let variable_node = &self.nodes[child_id];
-                    let variable_first_child_id = variable_node.children[0];
-                    let variable_first_child = &mut self.nodes[variable_first_child_id];
-
-                    let (annotations_node, annotations_node_id) =
-                        // If the first child is (annotations) node, then we add annotations to it
-                        if variable_first_child.grammar_name == "annotations" {
-                            annotations_node_exists = true;
-                            (variable_first_child, variable_first_child_id)
-                        // If variable doesn't already have (annotations) node, we create a new one
-                        } else {
-                            let annotations = GdTreeNode {
-                                parent_id: Some(child_id),
-                                grammar_id: annotations_grammar_id,
-                                grammar_name: "annotations",
-                                text: None,
-                                children: Vec::new(),
-                            };
-                            let annotations_id = self.nodes.len();
-                            self.nodes.push(annotations);
-                            (&mut self.nodes[annotations_id], annotations_id)
+                // We move from the current index back to 0, searching for
+                // annotations that belong to the declaration
+                let annotations_to_move = (0..index)
+                    .rev()
+                    .map_while(|i| {
+                        let child_id = self.nodes[parent_id].children[i];
+                        let child = &self.nodes[child_id];
+                        if child.grammar_name != "annotation" {
+                            return None;
+                        }
+                        let Some(annotation_name) = &self.nodes[child.children[0]].text else {
+                            return None;
                         };
+                        if STANDALONE_ANNOTATIONS.contains(&annotation_name.as_str()) {
+                            return None;
+                        }
+                        let parent = &mut self.nodes[parent_id];
+                        // When we found one, we remove it from the parent and collect them in a vector
+                        let annotation_id = parent.children.remove(i);
+                        index -= 1;
+                        Some(annotation_id)
+                    })
+                    .collect::<Vec<_>>();
+
+                if annotations_to_move.is_empty() {
+                    continue;
+                }
 
-                    for annotation_id in annotations_to_move {
-                        annotations_node.children.insert(0, annotation_id);
-                    }
+                let mut annotations_node_exists = false;
+
+                let declaration_node = &self.nodes[child_id];
+                let declaration_first_child_id = declaration_node.children[0];
+                let declaration_first_child = &mut self.nodes[declaration_first_child_id];
+
+                let (annotations_node, annotations_node_id) =
+                    // If the first child is (annotations) node, then we add annotations to it
+                    if declaration_first_child.grammar_name == "annotations" {
+                        annotations_node_exists = true;
+                        (declaration_first_child, declaration_first_child_id)
+                    // If the declaration doesn't already have (annotations) node, we create a new one
+                    } else {
+                        let annotations = GdTreeNode {
+                            parent_id: Some(child_id),
+                            grammar_id: annotations_grammar_id,
+                            grammar_name: "annotations",
+                            text: None,
+                            children: Vec::new(),
+                        };
+                        let annotations_id = self.nodes.len();
+                        self.nodes.push(annotations);
+                        (&mut self.nodes[annotations_id], annotations_id)
+                    };
 
-                    if !annotations_node_exists {
-                        let variable_node = &mut self.nodes[child_id];
-                        variable_node.children.insert(0, annotations_node_id);
-                    }
+                for annotation_id in annotations_to_move {
+                    annotations_node.children.insert(0, annotation_id);
+                }
+
+                if !annotations_node_exists {
+                    let declaration_node = &mut self.nodes[child_id];
+                    declaration_node.children.insert(0, annotations_node_id);
                 }
             }
         }
@@ -690,6 +1119,12 @@ impl PartialEq for GdTree {
                 if left_node.grammar_id != right_node.grammar_id {
                     return false;
                 }
+                // Leaf nodes like identifiers and comments must keep their text
+                if let (Some(left_text), Some(right_text)) = (&left_node.text, &right_node.text)
+                    && left_text != right_text
+                {
+                    return false;
+                }
                 left_stack.push(*left_node_id);
                 right_stack.push(*right_node_id);
             }
@@ -706,6 +1141,616 @@ struct GdTreeNode {
     children: Vec<usize>,
 }
 
+/// Node kinds of the comma-separated lists that can have a trailing comma.
+const COMMA_SEPARATED_LISTS: [&str; 5] = [
+    "array",
+    "dictionary",
+    "enumerator_list",
+    "arguments",
+    "parameters",
+];
+
+/// Returns the node and all its descendants of the given kinds, in document
+/// order.
+pub(crate) fn collect_nodes_of_kinds<'tree>(node: Node<'tree>, kinds: &[&str]) -> Vec<Node<'tree>> {
+    let mut nodes = Vec::new();
+    let mut cursor = node.walk();
+    loop {
+        let node = cursor.node();
+        if kinds.contains(&node.kind()) {
+            nodes.push(node);
+        }
+        if cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return nodes;
+            }
+        }
+    }
+}
+
+/// Returns all the lists that can have a trailing comma, in document order.
+fn collect_comma_separated_lists(tree: &Tree) -> Vec<Node<'_>> {
+    collect_nodes_of_kinds(tree.root_node(), &COMMA_SEPARATED_LISTS)
+}
+
+/// Returns whether each list has a trailing comma, in document order. We use
+/// this to preserve the trailing commas of the input code.
+fn collect_trailing_commas(tree: &Tree) -> Vec<(u16, bool)> {
+    collect_comma_separated_lists(tree)
+        .into_iter()
+        .map(|list| {
+            let has_trailing_comma =
+                find_trailing_comma(list).is_some_and(|(_, comma)| comma.is_some());
+            (list.kind_id(), has_trailing_comma)
+        })
+        .collect()
+}
+
+/// Returns the last element of a comma-separated list and the comma that
+/// follows it, if any. Returns None if the list is empty.
+fn find_trailing_comma(list: Node) -> Option<(Node, Option<Node>)> {
+    let mut cursor = list.walk();
+    let children: Vec<Node> = list.children(&mut cursor).collect();
+    let last_element_index = children
+        .iter()
+        .rposition(|child| child.is_named() && child.kind() != "comment")?;
+    let trailing_comma = children[last_element_index + 1..]
+        .iter()
+        .find(|child| child.kind() != "comment")
+        .filter(|child| child.kind() == ",")
+        .copied();
+    Some((children[last_element_index], trailing_comma))
+}
+
+/// Returns true if the node is the argument list of a call to `preload()`.
+fn is_preload_arguments(node: Node, content: &str) -> bool {
+    node.kind() == "arguments"
+        && node.parent().is_some_and(|call| {
+            call.kind() == "call"
+                && call
+                    .child(0)
+                    .is_some_and(|function| &content[function.byte_range()] == "preload")
+        })
+}
+
+/// Annotations that don't belong to the declaration below them, so the
+/// formatter never moves them onto its line.
+const STANDALONE_ANNOTATIONS: [&str; 8] = [
+    "tool",
+    "icon",
+    "static_unload",
+    "export_category",
+    "export_group",
+    "export_subgroup",
+    "warning_ignore_start",
+    "warning_ignore_restore",
+];
+
+/// Returns the name of an annotation, without the `@`.
+fn annotation_name<'a>(annotation: Node, content: &'a str) -> Option<&'a str> {
+    let mut cursor = annotation.walk();
+    let identifier = annotation
+        .named_children(&mut cursor)
+        .find(|child| child.kind() == "identifier")?;
+    Some(&content[identifier.byte_range()])
+}
+
+/// Returns the replacement that moves the annotations on the lines right above
+/// a declaration onto the declaration's line, like `@onready var`, as long as
+/// the line fits in `line_width` columns. The annotations closest to the
+/// declaration move first, the others stay on their own lines.
+fn inline_short_annotations(
+    declaration: Node,
+    content: &str,
+    line_width: usize,
+    indent_size: usize,
+) -> Option<(usize, usize, String)> {
+    let mut annotations = Vec::new();
+    let mut width = measure_line_width(content, declaration.start_byte(), indent_size);
+    let mut row = declaration.start_position().row;
+    let mut previous = declaration.prev_named_sibling();
+    while let Some(annotation) = previous {
+        let can_move = annotation.kind() == "annotation"
+            && annotation.start_position().row == annotation.end_position().row
+            && annotation_name(annotation, content)
+                .is_some_and(|name| !STANDALONE_ANNOTATIONS.contains(&name));
+        if !can_move || annotation.end_position().row + 1 != row {
+            break;
+        }
+        width += measure_width(&content[annotation.byte_range()], indent_size) + 1;
+        if width > line_width {
+            break;
+        }
+        annotations.push(annotation);
+        row = annotation.start_position().row;
+        previous = annotation.prev_named_sibling();
+    }
+
+    let first = *annotations.last()?;
+    let mut replacement: String = annotations
+        .iter()
+        .rev()
+        .map(|annotation| &content[annotation.byte_range()])
+        .collect::<Vec<_>>()
+        .join(" ");
+    replacement.push(' ');
+    Some((first.start_byte(), declaration.start_byte(), replacement))
+}
+
+/// Returns the replacement that moves the annotations on a declaration's line,
+/// like `@export var`, onto their own lines above the declaration.
+fn move_annotations_to_own_lines(
+    declaration: Node,
+    content: &str,
+) -> Option<(usize, usize, String)> {
+    let annotations = declaration
+        .named_child(0)
+        .filter(|child| child.kind() == "annotations")?;
+    let after_annotations = annotations.next_sibling()?;
+    let indent = find_line_indent(content, declaration)?;
+
+    let mut cursor = annotations.walk();
+    let line_break = format!("\n{indent}");
+    let mut replacement = annotations
+        .named_children(&mut cursor)
+        .map(|annotation| &content[annotation.byte_range()])
+        .collect::<Vec<_>>()
+        .join(&line_break);
+    replacement.push_str(&line_break);
+    Some((
+        annotations.start_byte(),
+        after_annotations.start_byte(),
+        replacement,
+    ))
+}
+
+/// Returns all the attribute nodes with at least two method calls, like
+/// `create_tween().set_trans(...)`, in document order.
+fn collect_method_chains(tree: &Tree) -> Vec<Node<'_>> {
+    collect_nodes_of_kinds(tree.root_node(), &["attribute"])
+        .into_iter()
+        .filter(|attribute| {
+            let mut cursor = attribute.walk();
+            let calls = attribute
+                .children(&mut cursor)
+                .enumerate()
+                .filter(|(index, child)| {
+                    child.kind() == "attribute_call" || (*index == 0 && child.kind() == "call")
+                })
+                .count();
+            calls >= 2
+        })
+        .collect()
+}
+
+/// Returns whether each method chain returned by `collect_method_chains` has a
+/// line break before one of its dots. We use this to keep the chains the user
+/// wrapped wrapped after formatting.
+fn collect_wrapped_chains(tree: &Tree) -> Vec<bool> {
+    collect_method_chains(tree)
+        .into_iter()
+        .map(|chain| {
+            let mut cursor = chain.walk();
+            let children: Vec<Node> = chain
+                .children(&mut cursor)
+                .filter(|child| child.kind() != "line_continuation")
+                .collect();
+            children.windows(2).any(|pair| {
+                pair[1].kind() == "." && pair[1].start_position().row > pair[0].end_position().row
+            })
+        })
+        .collect()
+}
+
+/// Returns the replacements that wrap a method chain with one call per line.
+/// The first line keeps everything up to the first call, and the following
+/// lines start with a dot and get one more level of indentation. Returns None
+/// if there is nothing to wrap or if the chain contains comments.
+fn wrap_method_chain(
+    chain: Node,
+    content: &str,
+    indent_string: &str,
+) -> Option<Vec<(usize, usize, String)>> {
+    let mut cursor = chain.walk();
+    let children: Vec<Node> = chain
+        .children(&mut cursor)
+        .filter(|child| child.kind() != "line_continuation")
+        .collect();
+    if children.iter().any(|child| child.kind() == "comment") {
+        return None;
+    }
+
+    let line_start = content[..chain.start_byte()]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let base_indent: String = content[line_start..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect();
+    let line_break = if is_inside_brackets(chain) {
+        format!("\n{base_indent}{indent_string}")
+    } else {
+        format!(" \\\n{base_indent}{indent_string}")
+    };
+
+    // Whitespace and line continuations between each dot and the node before
+    // it, with what should replace them
+    let mut gaps = Vec::new();
+    let mut has_call = children.first().is_some_and(|child| child.kind() == "call");
+    for (index, child) in children.iter().enumerate().skip(1) {
+        if child.kind() != "." {
+            continue;
+        }
+        let is_call = children
+            .get(index + 1)
+            .is_some_and(|next| next.kind() == "attribute_call");
+        let replacement = if is_call && has_call {
+            line_break.clone()
+        } else {
+            String::new()
+        };
+        has_call |= is_call;
+        gaps.push((
+            children[index - 1].end_byte(),
+            child.start_byte(),
+            replacement,
+        ));
+    }
+    if gaps
+        .iter()
+        .all(|(_, _, replacement)| replacement.is_empty())
+    {
+        return None;
+    }
+
+    // Lines that were already inside the chain, like multi-line arguments,
+    // move along with their call, except inside multi-line strings
+    let strings = collect_nodes_of_kinds(chain, &["string"]);
+    let mut replacements: Vec<(usize, usize, String)> = content[chain.byte_range()]
+        .match_indices('\n')
+        .map(|(offset, _)| chain.start_byte() + offset)
+        .filter(|&newline| {
+            let is_blank_line = content[newline + 1..].starts_with(['\n', '\r']);
+            let is_in_gap = gaps
+                .iter()
+                .any(|(start, end, _)| (*start..*end).contains(&newline));
+            let is_in_string = strings
+                .iter()
+                .any(|string| string.byte_range().contains(&newline));
+            !is_blank_line && !is_in_gap && !is_in_string
+        })
+        .map(|newline| (newline + 1, newline + 1, indent_string.to_string()))
+        .collect();
+
+    replacements.extend(
+        gaps.into_iter()
+            .filter(|(start, end, replacement)| content[*start..*end] != *replacement),
+    );
+    replacements.sort_by_key(|(start, _, _)| *start);
+    Some(replacements)
+}
+
+/// Returns true if the node is inside parentheses, brackets, or braces in the
+/// same statement, where GDScript allows line breaks without a backslash.
+fn is_inside_brackets(node: Node) -> bool {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        match parent.kind() {
+            "arguments"
+            | "parenthesized_expression"
+            | "array"
+            | "dictionary"
+            | "subscript_arguments" => return true,
+            "body" | "class_body" | "source" | "lambda" => return false,
+            _ => current = parent.parent(),
+        }
+    }
+    false
+}
+
+/// Returns the width of the line containing `byte_index`, counting tabs as
+/// `indent_size` columns.
+fn measure_line_width(content: &str, byte_index: usize, indent_size: usize) -> usize {
+    let line_start = content[..byte_index]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let line_end = content[byte_index..]
+        .find('\n')
+        .map_or(content.len(), |index| byte_index + index);
+    measure_width(&content[line_start..line_end], indent_size)
+}
+
+/// Returns the width of the text, counting tabs as `indent_size` columns.
+fn measure_width(text: &str, indent_size: usize) -> usize {
+    text.chars()
+        .map(|c| if c == '\t' { indent_size } else { 1 })
+        .sum()
+}
+
+/// Returns the whitespace before the node if it's the first thing on its line,
+/// None otherwise.
+fn find_line_indent<'a>(content: &'a str, node: Node) -> Option<&'a str> {
+    let line_start = content[..node.start_byte()]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let indent = &content[line_start..node.start_byte()];
+    indent.trim().is_empty().then_some(indent)
+}
+
+/// Returns true if the comment is a `##` doc comment.
+fn is_doc_comment(comment: &str) -> bool {
+    comment.starts_with("##") && !comment.starts_with("###")
+}
+
+/// Returns the comment with a space between its `#` or `##` prefix and its
+/// text, or None if the comment should stay as is.
+fn add_space_after_hash(comment: &str) -> Option<String> {
+    let text = comment.trim_start_matches('#');
+    let hashes = &comment[..comment.len() - text.len()];
+    if hashes.len() > 2 || text.is_empty() || text.starts_with(char::is_whitespace) {
+        return None;
+    }
+    // Shebangs and directives only work without the space. A directive is a
+    // whole word, so `#regional` or `#fmtstr` are regular comments
+    let is_directive = text.starts_with('!')
+        || ["region", "endregion", "gdlint", "fmt"]
+            .iter()
+            .any(|directive| {
+                text.strip_prefix(directive).is_some_and(|rest| {
+                    rest.is_empty() || rest.starts_with(|c: char| c.is_whitespace() || c == ':')
+                })
+            });
+    if is_directive {
+        return None;
+    }
+    Some(format!("{hashes} {text}"))
+}
+
+/// Appends a comment to the normalized text of a sequence of consecutive
+/// comments, for safe mode to compare comments before and after formatting
+/// them. We drop the spacing that formatting changes: the space after the `#`,
+/// the spaces between words, and the line breaks between the words of `##`
+/// doc comments. Empty `##` lines separate paragraphs, so they stay.
+fn push_normalized_comment(normalized: &mut String, comment: &str) {
+    let text = comment.trim_start_matches('#');
+    let hashes = &comment[..comment.len() - text.len()];
+    let words = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let continues_doc_paragraph = hashes == "##"
+        && !words.is_empty()
+        && normalized
+            .rsplit('\n')
+            .next()
+            .is_some_and(|last_line| last_line.starts_with("## "));
+    if continues_doc_paragraph {
+        normalized.push(' ');
+    } else {
+        if !normalized.is_empty() {
+            normalized.push('\n');
+        }
+        normalized.push_str(hashes);
+        if !words.is_empty() {
+            normalized.push(' ');
+        }
+    }
+    normalized.push_str(&words);
+}
+
+/// Returns a number literal written the same way whatever the number
+/// normalization options, for safe mode to compare literals by value.
+fn normalize_number_for_comparison(literal: &str) -> String {
+    let literal = literal.replace('_', "").to_ascii_lowercase();
+    let is_decimal_float =
+        !literal.starts_with("0x") && !literal.starts_with("0b") && literal.contains(['.', 'e']);
+    match literal.parse::<f64>() {
+        Ok(value) if is_decimal_float => format!("{value:?}"),
+        _ => literal,
+    }
+}
+
+/// Reflows the lines of a doc comment block so the text of each line fits in
+/// `available_width` columns, and returns the new lines.
+///
+/// Paragraphs are separated by empty `##` lines. List items, lines starting
+/// with a tag like `@tutorial`, and lines indented differently from the line
+/// before start a new paragraph. Paragraphs keep the indentation of their
+/// first line after the `## `, and we copy the lines of `[codeblock]` and
+/// `[codeblocks]` blocks as they are.
+fn reflow_doc_comment(lines: &[&str], available_width: usize) -> Vec<String> {
+    let mut output = Vec::new();
+    let mut paragraph = String::new();
+    let mut paragraph_indent = "";
+    let mut is_in_code_block = false;
+
+    for line in lines {
+        let content = &line[2..];
+        let content = content.strip_prefix(' ').unwrap_or(content);
+        let text = content.trim();
+        if is_in_code_block {
+            output.push(line.to_string());
+            is_in_code_block = !text.starts_with("[/codeblock");
+            continue;
+        }
+
+        let indent = &content[..content.len() - content.trim_start().len()];
+        let is_code_block_start = text.starts_with("[codeblock");
+        let is_tag = text.starts_with('@');
+        let is_list_item = text.starts_with("- ") || text.starts_with("* ");
+        if text.is_empty()
+            || is_code_block_start
+            || is_tag
+            || is_list_item
+            || indent != paragraph_indent
+        {
+            wrap_doc_paragraph(&paragraph, paragraph_indent, available_width, &mut output);
+            paragraph.clear();
+            paragraph_indent = indent;
+        }
+
+        if is_code_block_start {
+            output.push(line.to_string());
+            is_in_code_block = !text.contains("[/codeblock");
+        } else if is_tag {
+            output.push(format!("## {indent}{text}"));
+        } else if text.is_empty() {
+            output.push("##".to_string());
+        } else {
+            if !paragraph.is_empty() {
+                paragraph.push(' ');
+            }
+            paragraph.push_str(text);
+        }
+    }
+    wrap_doc_paragraph(&paragraph, paragraph_indent, available_width, &mut output);
+    output
+}
+
+/// Greedily wraps the words of a doc comment paragraph into `## ` lines
+/// indented by `indent`. Inline `[code]` spans are never split, as their
+/// spaces are significant.
+fn wrap_doc_paragraph(
+    paragraph: &str,
+    indent: &str,
+    available_width: usize,
+    output: &mut Vec<String>,
+) {
+    let mut words: Vec<String> = Vec::new();
+    let mut is_in_code_span = false;
+    for word in paragraph.split_whitespace() {
+        match words.last_mut() {
+            Some(last) if is_in_code_span => {
+                last.push(' ');
+                last.push_str(word);
+            }
+            _ => words.push(word.to_string()),
+        }
+        let current = words.last().unwrap();
+        is_in_code_span = current.matches("[code]").count() > current.matches("[/code]").count();
+    }
+
+    let available_width = available_width.saturating_sub(indent.chars().count());
+    let mut line = String::new();
+    for word in words {
+        let width = line.chars().count() + 1 + word.chars().count();
+        if !line.is_empty() && width > available_width {
+            output.push(format!("## {indent}{line}"));
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&word);
+    }
+    if !line.is_empty() {
+        output.push(format!("## {indent}{line}"));
+    }
+}
+
+/// Returns the statements and members whose vertical spacing the user
+/// controls, in document order: the direct children of function bodies,
+/// classes, and the source file.
+fn collect_spaced_nodes(tree: &Tree) -> Vec<Node<'_>> {
+    let mut nodes = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        // We push children in reverse order to visit them in document order
+        for child in children.into_iter().rev() {
+            stack.push(child);
+        }
+        if node
+            .parent()
+            .is_some_and(|parent| matches!(parent.kind(), "source" | "class_body" | "body"))
+        {
+            nodes.push(node);
+        }
+    }
+    nodes
+}
+
+/// Returns the kind and the number of blank lines above each node returned by
+/// `collect_spaced_nodes`. We use this to restore the user's blank lines after
+/// formatting.
+fn collect_blank_lines(tree: &Tree, content: &str) -> Vec<(u16, usize)> {
+    collect_spaced_nodes(tree)
+        .into_iter()
+        .map(|node| {
+            let blank_lines = find_blank_lines_above(content, node)
+                .map_or(0, |(run_start, line_start)| {
+                    count_lines(&content[run_start..line_start])
+                });
+            (node.kind_id(), blank_lines)
+        })
+        .collect()
+}
+
+/// Returns true if the spacing above this class member is controlled by the
+/// maximum number of blank lines between properties. Spacing around methods
+/// and inner classes, and after the extends statement, follows other settings.
+fn is_spaced_as_property(node: Node) -> bool {
+    const DEFINITIONS: [&str; 3] = [
+        "function_definition",
+        "constructor_definition",
+        "class_definition",
+    ];
+    const HEADER: [&str; 2] = ["extends_statement", "class_name_statement"];
+
+    // Comments and annotations take the spacing of the declaration below them
+    let mut next = Some(node);
+    while let Some(current) = next
+        && matches!(current.kind(), "comment" | "annotation")
+    {
+        next = current.next_named_sibling();
+    }
+    if next.is_some_and(|next| DEFINITIONS.contains(&next.kind())) {
+        return false;
+    }
+
+    let mut previous = node.prev_named_sibling();
+    while let Some(current) = previous
+        && current.kind() == "comment"
+    {
+        previous = current.prev_named_sibling();
+    }
+    !previous.is_some_and(|previous| {
+        DEFINITIONS.contains(&previous.kind()) || HEADER.contains(&previous.kind())
+    })
+}
+
+/// If `node` is the first thing on its line, returns the byte range of the
+/// blank lines right above it, as `(start, end)`. The range is empty if there
+/// are no blank lines.
+fn find_blank_lines_above(content: &str, node: Node) -> Option<(usize, usize)> {
+    let line_start = node.start_byte() - node.start_position().column;
+    if !content[line_start..node.start_byte()].trim().is_empty() {
+        return None;
+    }
+
+    let bytes = content.as_bytes();
+    let mut run_start = line_start;
+    while run_start > 0 {
+        // Find the start of the line above and check if it's blank
+        let previous_line_start = bytes[..run_start - 1]
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map_or(0, |index| index + 1);
+        if !content[previous_line_start..run_start].trim().is_empty() {
+            break;
+        }
+        run_start = previous_line_start;
+    }
+    Some((run_start, line_start))
+}
+
+/// Counts the line breaks in `slice`.
+fn count_lines(slice: &str) -> usize {
+    slice.bytes().filter(|b| *b == b'\n').count()
+}
+
 /// Calculates end position of the `slice` counting from `start`
 fn calculate_end_position(mut start: Point, slice: &str) -> Point {
     for b in slice.as_bytes() {
@@ -718,3 +1763,96 @@ fn calculate_end_position(mut start: Point, slice: &str) -> Point {
     }
     start
 }
+
+/// Returns the canonical spelling of a GDScript number literal. See
+/// `Formatter::normalize_number_literals` for the rules.
+fn normalize_number_literal(
+    literal: &str,
+    hex_digit_case: HexDigitCase,
+    group_digits: Option<usize>,
+) -> String {
+    let lowercase = literal.to_ascii_lowercase();
+    if let Some(lowercase_digits) = lowercase.strip_prefix("0x") {
+        let digits = match hex_digit_case {
+            HexDigitCase::Lower => lowercase_digits.to_string(),
+            HexDigitCase::Upper => lowercase_digits.to_ascii_uppercase(),
+            HexDigitCase::Preserve => literal[2..].to_string(),
+        };
+        return format!("0x{digits}");
+    }
+    if lowercase.starts_with("0b") {
+        return lowercase;
+    }
+
+    // Decimal literals are made of an integer part, an optional fraction, and
+    // an optional exponent: 1_000.5e-3
+    let (mantissa, exponent) = match lowercase.find('e') {
+        Some(index) => lowercase.split_at(index),
+        None => (lowercase.as_str(), ""),
+    };
+    let (integer_part, fraction) = match mantissa.split_once('.') {
+        Some((integer_part, fraction)) => (integer_part, Some(fraction)),
+        None => (mantissa, None),
+    };
+
+    let mut result = String::with_capacity(literal.len() + 2);
+    let digit_count = integer_part.bytes().filter(u8::is_ascii_digit).count();
+    match group_digits {
+        Some(min_digits) if digit_count >= min_digits => {
+            result.push_str(&group_by_thousands(integer_part));
+        }
+        _ if integer_part.is_empty() => result.push('0'),
+        _ => result.push_str(integer_part),
+    }
+    if let Some(fraction) = fraction {
+        result.push('.');
+        result.push_str(if fraction.is_empty() { "0" } else { fraction });
+    }
+    result.push_str(exponent);
+    result
+}
+
+/// Inserts an underscore between every group of three digits, counting from
+/// the right: 1234567 becomes 1_234_567. Existing underscores are discarded.
+fn group_by_thousands(digits: &str) -> String {
+    let digits: Vec<char> = digits.chars().filter(|c| *c != '_').collect();
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.iter().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            result.push('_');
+        }
+        result.push(*digit);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_mode_detects_changed_comments() {
+        let input = "## The speed of the player in pixels per second.\n## It can't be negative.\nvar speed = 0.5\n";
+        let config = FormatterConfig {
+            reflow_comments: true,
+            safe: true,
+            ..Default::default()
+        };
+        let finish_with = |output: &str| {
+            let mut formatter = Formatter::new(input.to_string(), config.clone());
+            formatter.content = output.to_string();
+            formatter.finish()
+        };
+
+        let reflowed = "## The speed of the player in pixels per second. It can't be\n## negative.\nvar speed = 0.5\n";
+        assert!(finish_with(reflowed).is_ok());
+        let dropped_words = "## The speed of the player in pixels per second.\nvar speed = 0.5\n";
+        assert!(finish_with(dropped_words).is_err());
+        let reordered_words = "## The speed of the player in pixels per second.\n## It can't negative be.\nvar speed = 0.5\n";
+        assert!(finish_with(reordered_words).is_err());
+    }
+}
@@ -0,0 +1,278 @@
+//! This module gives access to Godot's API offline: the classes of the engine,
+//! what they inherit from, and their methods, properties, and signals, plus the
+//! global functions, types, and singletons.
+//!
+//! The data comes from the `extension_api.json` file Godot generates with
+//! `godot --headless --dump-extension-api`. Users can load the full file of
+//! their Godot version with `lint --godot-api`.
+//!
+//! The bundled file, `data/extension_api.json.zlib`, is NOT a full dump. It's a
+//! hand-picked subset of Godot 4.4's API in the same format: the built-in types,
+//! the global singletons and utility functions, and only 21 engine classes,
+//! the ones scripts extend the most, with their inheritance chains (Object,
+//! RefCounted, Resource, Node, Timer, CanvasItem, Node2D, Sprite2D, the 2D and
+//! 3D physics bodies, Node3D, Control, Label, BaseButton, and Button). Classes
+//! like AnimationPlayer are missing, so for scripts that extend them, the rules
+//! don't know the members of the base class unless users pass `--godot-api`.
+//!
+//! To replace it with a complete dump, compress a dumped `extension_api.json`,
+//! for example with
+//! `python3 -c "import sys, zlib; sys.stdout.buffer.write(zlib.compress(sys.stdin.buffer.read(), 9))"`.
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+use serde_json::Value;
+
+static BUNDLED_API: &[u8] = include_bytes!("../data/extension_api.json.zlib");
+
+static BUNDLED: LazyLock<Arc<GodotApi>> = LazyLock::new(|| {
+    let json = miniz_oxide::inflate::decompress_to_vec_zlib(BUNDLED_API)
+        .expect("the bundled Godot API should be valid zlib data");
+    let json = String::from_utf8(json).expect("the bundled Godot API should be UTF-8");
+    Arc::new(GodotApi::from_json(&json).expect("the bundled Godot API should be valid"))
+});
+
+/// Functions built into GDScript. They're not part of the engine's API, so
+/// they're not in `extension_api.json`.
+const GDSCRIPT_FUNCTIONS: &[&str] = &[
+    "Color8",
+    "assert",
+    "char",
+    "convert",
+    "dict_to_inst",
+    "get_stack",
+    "inst_to_dict",
+    "is_instance_of",
+    "len",
+    "load",
+    "preload",
+    "print_debug",
+    "print_stack",
+    "range",
+    "type_exists",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberKind {
+    Property,
+    Method,
+    Signal,
+}
+
+impl MemberKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemberKind::Property => "property",
+            MemberKind::Method => "method",
+            MemberKind::Signal => "signal",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ApiClass {
+    pub name: String,
+    /// The class this class extends. Only Object has none.
+    pub inherits: Option<String>,
+    pub methods: Vec<String>,
+    /// The methods Godot calls and scripts can override, like `_ready`
+    pub virtual_methods: Vec<String>,
+    pub properties: Vec<String>,
+    pub signals: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GodotApi {
+    /// Version of Godot the API comes from, like "4.4"
+    pub version: String,
+    classes: HashMap<String, ApiClass>,
+    builtin_types: HashSet<String>,
+    singletons: HashSet<String>,
+    utility_functions: HashSet<String>,
+}
+
+impl GodotApi {
+    /// Returns the API bundled with the formatter.
+    pub fn bundled() -> Arc<GodotApi> {
+        Arc::clone(&BUNDLED)
+    }
+
+    /// Loads an `extension_api.json` file dumped by Godot.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+        Self::from_json(&json)
+            .map_err(|error| format!("Failed to load the Godot API {}: {}", path.display(), error))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let root: Value = serde_json::from_str(json).map_err(|error| error.to_string())?;
+
+        let header = &root["header"];
+        let version = match (
+            header["version_major"].as_u64(),
+            header["version_minor"].as_u64(),
+        ) {
+            (Some(major), Some(minor)) => format!("{}.{}", major, minor),
+            _ => return Err("missing Godot version in the header".to_string()),
+        };
+
+        let mut classes = HashMap::new();
+        for class in array(&root, "classes") {
+            let Some(name) = class["name"].as_str() else {
+                continue;
+            };
+            let mut api_class = ApiClass {
+                name: name.to_string(),
+                inherits: class["inherits"].as_str().map(str::to_string),
+                properties: names(class, "properties"),
+                signals: names(class, "signals"),
+                ..Default::default()
+            };
+            for method in array(class, "methods") {
+                if let Some(method_name) = method["name"].as_str() {
+                    if method["is_virtual"].as_bool().unwrap_or(false) {
+                        api_class.virtual_methods.push(method_name.to_string());
+                    } else {
+                        api_class.methods.push(method_name.to_string());
+                    }
+                }
+            }
+            classes.insert(name.to_string(), api_class);
+        }
+
+        Ok(Self {
+            version,
+            classes,
+            builtin_types: names(&root, "builtin_classes").into_iter().collect(),
+            singletons: names(&root, "singletons").into_iter().collect(),
+            utility_functions: names(&root, "utility_functions").into_iter().collect(),
+        })
+    }
+
+    pub fn class(&self, name: &str) -> Option<&ApiClass> {
+        self.classes.get(name)
+    }
+
+    /// Returns the class and the classes it inherits from, starting with the
+    /// class itself.
+    pub fn ancestors<'a>(&'a self, class_name: &str) -> impl Iterator<Item = &'a ApiClass> {
+        std::iter::successors(self.class(class_name), |class| {
+            class
+                .inherits
+                .as_deref()
+                .and_then(|parent| self.class(parent))
+        })
+    }
+
+    /// Returns true if the class is `ancestor` or inherits from it.
+    pub fn inherits(&self, class_name: &str, ancestor: &str) -> bool {
+        self.ancestors(class_name)
+            .any(|class| class.name == ancestor)
+    }
+
+    /// Returns the class that defines a member, looking through the class and
+    /// the classes it inherits from, and the kind of the member.
+    pub fn find_member(&self, class_name: &str, member_name: &str) -> Option<(&str, MemberKind)> {
+        let is_member = |members: &[String]| members.iter().any(|member| member == member_name);
+        self.ancestors(class_name).find_map(|class| {
+            let kind = if is_member(&class.properties) {
+                MemberKind::Property
+            } else if is_member(&class.methods) || is_member(&class.virtual_methods) {
+                MemberKind::Method
+            } else if is_member(&class.signals) {
+                MemberKind::Signal
+            } else {
+                return None;
+            };
+            Some((class.name.as_str(), kind))
+        })
+    }
+
+    /// Returns true if Godot calls the method on instances of the class, like
+    /// `_ready` on nodes.
+    pub fn is_virtual_method(&self, class_name: &str, method_name: &str) -> bool {
+        self.ancestors(class_name).any(|class| {
+            class
+                .virtual_methods
+                .iter()
+                .any(|method| method == method_name)
+        })
+    }
+
+    /// Returns true if the name is a global function, a built-in type, a class,
+    /// or a singleton of Godot.
+    pub fn is_global_identifier(&self, name: &str) -> bool {
+        GDSCRIPT_FUNCTIONS.contains(&name)
+            || self.utility_functions.contains(name)
+            || self.builtin_types.contains(name)
+            || self.singletons.contains(name)
+            || self.classes.contains_key(name)
+    }
+}
+
+fn array<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+    value[key].as_array().into_iter().flatten()
+}
+
+/// Returns the `name` field of each object in the array at `key`.
+fn names(value: &Value, key: &str) -> Vec<String> {
+    array(value, key)
+        .filter_map(|item| item["name"].as_str())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_api() {
+        let api = GodotApi::bundled();
+        assert!(api.inherits("CharacterBody2D", "Node2D"));
+        assert!(!api.inherits("Node2D", "Control"));
+        assert_eq!(
+            api.find_member("Sprite2D", "position"),
+            Some(("Node2D", MemberKind::Property))
+        );
+        assert!(api.is_virtual_method("Control", "_gui_input"));
+        assert!(!api.is_virtual_method("Node2D", "_gui_input"));
+        assert!(api.is_global_identifier("print"));
+        assert!(api.is_global_identifier("Vector2"));
+        assert!(api.is_global_identifier("preload"));
+        assert!(!api.is_global_identifier("player"));
+    }
+
+    #[test]
+    fn test_load_api_from_json() {
+        let json = r#"{
+            "header": {"version_major": 4, "version_minor": 5},
+            "classes": [
+                {"name": "Object", "methods": [{"name": "free", "is_virtual": false}]},
+                {
+                    "name": "Node",
+                    "inherits": "Object",
+                    "methods": [{"name": "_ready", "is_virtual": true}],
+                    "signals": [{"name": "ready"}]
+                }
+            ],
+            "utility_functions": [{"name": "print"}]
+        }"#;
+
+        let api = GodotApi::from_json(json).unwrap();
+        assert_eq!(api.version, "4.5");
+        assert_eq!(
+            api.find_member("Node", "free"),
+            Some(("Object", MemberKind::Method))
+        );
+        assert_eq!(
+            api.find_member("Node", "ready"),
+            Some(("Node", MemberKind::Signal))
+        );
+        assert!(api.is_virtual_method("Node", "_ready"));
+        assert!(api.is_global_identifier("print"));
+        assert!(GodotApi::from_json("{}").is_err());
+    }
+}
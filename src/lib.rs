@@ -1,6 +1,10 @@
+pub mod code_blocks;
 pub mod formatter;
-pub mod reorder;
+pub mod godot_api;
 pub mod linter;
+pub mod migrate;
+pub mod reorder;
+pub mod resource;
 
 #[derive(Clone)]
 pub struct FormatterConfig {
@@ -8,6 +12,66 @@ pub struct FormatterConfig {
     pub use_spaces: bool,
     pub reorder_code: bool,
     pub safe: bool,
+    /// If true, number literals are rewritten to a canonical form: lowercase
+    /// `0x`/`0b` prefixes and exponents, and explicit zeros around the decimal
+    /// point of floats (`.5` becomes `0.5`).
+    pub normalize_numbers: bool,
+    /// Letter case of hexadecimal digits when normalizing number literals.
+    pub hex_digit_case: HexDigitCase,
+    /// When normalizing number literals, decimal literals whose integer part
+    /// has at least this many digits get grouped by thousands with underscores.
+    pub group_digits: Option<usize>,
+    /// Number of blank lines between methods, and between methods and other
+    /// class members.
+    pub blank_lines_between_methods: usize,
+    /// Number of blank lines before and after inner classes.
+    pub blank_lines_between_classes: usize,
+    /// Maximum number of consecutive blank lines kept between class members
+    /// that aren't methods or inner classes, like groups of properties.
+    pub max_blank_lines_between_properties: usize,
+    /// Number of blank lines after the `extends` statement at the top of the
+    /// file.
+    pub blank_lines_after_extends: usize,
+    /// Maximum number of consecutive blank lines kept between statements
+    /// inside function bodies.
+    pub max_blank_lines_in_functions: usize,
+    /// Whether multi-line arrays, dictionaries, enums, arguments, and
+    /// parameters end with a comma after their last element.
+    pub trailing_commas: TrailingCommas,
+    /// Maximum line width the formatter aims for, counting tabs as
+    /// `indent_size` columns. Method chains that make a line longer than this
+    /// get wrapped with one call per line.
+    pub line_width: usize,
+    /// If true, comments get a space after their `#` and paragraphs of `##`
+    /// doc comments are reflowed to fit in `line_width`.
+    pub reflow_comments: bool,
+    /// Placement of the annotations of variables, like `@export` or
+    /// `@onready`.
+    pub variable_annotations: AnnotationPlacement,
+    /// Placement of the annotations of functions, like `@rpc`.
+    pub function_annotations: AnnotationPlacement,
+    /// Major version of Godot the scripts target. Godot 3 scripts use a
+    /// different syntax for some constructs, like `onready var` or `setget`.
+    pub godot_version: GodotVersion,
+    /// Order of the categories of declarations when reordering code.
+    /// Categories missing from the list go after the listed ones, in the
+    /// default order.
+    pub declaration_order: Vec<DeclarationCategory>,
+    /// Built-in virtual methods like `_init` or `_ready`, in the order they
+    /// should appear when reordering code. Other methods are sorted like
+    /// regular methods. If None, we use the style guide's list for the
+    /// targeted Godot version.
+    pub virtual_methods: Option<Vec<String>>,
+    /// If true, reordering code puts pseudo-private declarations, whose name
+    /// starts with an underscore, after the public ones of the same category.
+    pub private_members_last: bool,
+    /// Where reordering code puts `#region` blocks. The declarations inside of
+    /// a region always stay in it and get sorted.
+    pub region_order: RegionOrder,
+    /// If true, reordering code fails on files with regions it can't keep
+    /// intact, like a #region without a matching #endregion, instead of moving
+    /// the region markers with the declarations that follow them.
+    pub strict_regions: bool,
 }
 
 impl Default for FormatterConfig {
@@ -17,6 +81,129 @@ impl Default for FormatterConfig {
             use_spaces: false,
             reorder_code: false,
             safe: false,
+            normalize_numbers: false,
+            hex_digit_case: HexDigitCase::Lower,
+            group_digits: None,
+            blank_lines_between_methods: 2,
+            blank_lines_between_classes: 2,
+            max_blank_lines_between_properties: 1,
+            blank_lines_after_extends: 1,
+            max_blank_lines_in_functions: 1,
+            trailing_commas: TrailingCommas::Always,
+            line_width: 100,
+            reflow_comments: false,
+            variable_annotations: AnnotationPlacement::Preserve,
+            function_annotations: AnnotationPlacement::Preserve,
+            godot_version: GodotVersion::Godot4,
+            declaration_order: DeclarationCategory::DEFAULT_ORDER.to_vec(),
+            virtual_methods: None,
+            private_members_last: true,
+            region_order: RegionOrder::Fixed,
+            strict_regions: false,
         }
     }
 }
+
+/// Major version of Godot that GDScript files target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum GodotVersion {
+    /// Godot 3.x, with GDScript 1.0 syntax like `export var` and `setget`
+    #[value(name = "3")]
+    Godot3,
+    /// Godot 4.x, with annotations like `@export` and `@onready`
+    #[value(name = "4")]
+    Godot4,
+}
+
+/// Categories of class members that reordering code groups together. The
+/// header of the file, with annotations like `@tool`, `class_name`, and
+/// `extends`, always comes first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeclarationCategory {
+    Signals,
+    Enums,
+    Constants,
+    /// `static var` declarations
+    StaticVariables,
+    /// Variables with an @export annotation
+    ExportVariables,
+    /// Variables without @export or @onready annotations
+    Variables,
+    /// Variables with an @onready annotation
+    OnreadyVariables,
+    /// The `_static_init()` method
+    StaticInit,
+    /// `static func` declarations
+    StaticMethods,
+    /// Built-in virtual methods, like `_init()` or `_ready()`
+    VirtualMethods,
+    /// All the other methods
+    Methods,
+    InnerClasses,
+}
+
+impl DeclarationCategory {
+    /// The order of the official GDScript style guide.
+    pub const DEFAULT_ORDER: &[Self] = &[
+        Self::Signals,
+        Self::Enums,
+        Self::Constants,
+        Self::StaticVariables,
+        Self::ExportVariables,
+        Self::Variables,
+        Self::OnreadyVariables,
+        Self::StaticInit,
+        Self::StaticMethods,
+        Self::VirtualMethods,
+        Self::Methods,
+        Self::InnerClasses,
+    ];
+}
+
+/// Where reordering code puts `#region` blocks relative to other declarations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum RegionOrder {
+    /// Regions stay where they are, and other declarations get sorted around
+    /// them
+    Fixed,
+    /// Regions move with their first declaration, as if they were this
+    /// declaration
+    FirstMember,
+}
+
+/// Letter case used for the digits of hexadecimal number literals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum HexDigitCase {
+    /// `0xff00aa`, as recommended by the official style guide
+    Lower,
+    /// `0xFF00AA`
+    Upper,
+    /// Keep the digits as written
+    Preserve,
+}
+
+/// Placement of the annotations of a declaration relative to the declaration's
+/// first line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum AnnotationPlacement {
+    /// Move annotations like `@onready` or `@export_range(0, 10)` onto the
+    /// declaration's line when the line fits in `line_width`, and keep the
+    /// others as written
+    Inline,
+    /// Put every annotation on its own line above the declaration
+    OwnLine,
+    /// Keep annotations as written, except `@export` and `@onready` without
+    /// arguments, which go on the declaration's line
+    Preserve,
+}
+
+/// Policy for the comma after the last element of multi-line lists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum TrailingCommas {
+    /// Add a trailing comma to every multi-line list
+    Always,
+    /// Remove trailing commas from multi-line lists
+    Never,
+    /// Keep trailing commas as written
+    Preserve,
+}
@@ -1,19 +1,28 @@
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::{fs, io::IsTerminal};
 use tree_sitter::{Node, Parser};
 
+pub mod control_flow;
 pub mod ignore_patterns;
 pub mod lib;
+pub mod metrics;
 pub mod regex_patterns;
 pub mod rule_config;
 pub mod rules;
+pub mod semantic;
 
 #[cfg(test)]
 mod tests;
 
 use ignore_patterns::{parse_ignore_patterns, should_ignore_rule};
-use rules::{ALL_RULES, Rule};
+use rules::{ALL_RULES, LintContext, Rule};
+use semantic::SemanticModel;
+
+use crate::GodotVersion;
+use crate::godot_api::GodotApi;
+use crate::resource::{find_embedded_scripts, is_resource_file};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum LintSeverity {
@@ -28,6 +37,17 @@ pub struct LintIssue {
     pub rule: String,
     pub severity: LintSeverity,
     pub message: String,
+    /// A change to the source code that fixes the issue, for `lint --fix`
+    pub fix: Option<LintFix>,
+}
+
+/// Replaces the bytes between `start_byte` and `end_byte` of the source code
+/// with `replacement`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintFix {
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub replacement: String,
 }
 
 impl LintIssue {
@@ -44,9 +64,15 @@ impl LintIssue {
             rule,
             severity,
             message,
+            fix: None,
         }
     }
 
+    pub fn with_fix(mut self, fix: Option<LintFix>) -> Self {
+        self.fix = fix;
+        self
+    }
+
     pub fn format(&self, file_path: &str) -> String {
         let severity_str = match self.severity {
             LintSeverity::Error => "error",
@@ -62,14 +88,54 @@ impl LintIssue {
 #[derive(Debug, Clone)]
 pub struct LinterConfig {
     pub disabled_rules: HashSet<String>,
+    /// Rules that are off by default to run, like the static typing rules
+    pub enabled_rules: HashSet<String>,
     pub max_line_length: usize,
+    /// Limits of the size and complexity of functions, see the `metrics`
+    /// module for how we measure them
+    pub max_function_lines: usize,
+    pub max_function_statements: usize,
+    pub max_cyclomatic_complexity: usize,
+    pub max_nesting_depth: usize,
+    pub max_arguments: usize,
+    pub max_returns: usize,
+    /// Limits of the size of files and classes
+    pub max_file_lines: usize,
+    pub max_public_methods: usize,
+    pub max_class_variables: usize,
+    pub max_inner_classes: usize,
+    pub max_signals: usize,
+    /// If false, the static typing rules report declarations that infer their
+    /// type with `:=`, like `var health := 100`
+    pub allow_inferred_types: bool,
+    /// Major version of Godot the scripts target. The linter flags syntax that
+    /// doesn't exist in this version.
+    pub godot_version: GodotVersion,
+    /// Godot's classes and global identifiers, for rules that depend on the
+    /// engine's API
+    pub godot_api: Arc<GodotApi>,
 }
 
 impl Default for LinterConfig {
     fn default() -> Self {
         Self {
             disabled_rules: HashSet::new(),
+            enabled_rules: HashSet::new(),
             max_line_length: 100,
+            max_function_lines: 100,
+            max_function_statements: 50,
+            max_cyclomatic_complexity: 10,
+            max_nesting_depth: 5,
+            max_arguments: 10,
+            max_returns: 6,
+            max_file_lines: 1000,
+            max_public_methods: 20,
+            max_class_variables: 30,
+            max_inner_classes: 5,
+            max_signals: 10,
+            allow_inferred_types: true,
+            godot_version: GodotVersion::Godot4,
+            godot_api: GodotApi::bundled(),
         }
     }
 }
@@ -96,13 +162,21 @@ impl GDScriptLinter {
             .ok_or("Failed to parse GDScript code")?;
 
         let root_node = tree.root_node();
+        let semantic = SemanticModel::build(&root_node, source_code);
+        let context = LintContext {
+            source_code,
+            semantic: &semantic,
+            godot_api: &self.config.godot_api,
+        };
         let mut issues = Vec::new();
 
         let ignore_map = parse_ignore_patterns(source_code);
 
         let mut checkers: Vec<Box<dyn Rule>> = Vec::new();
         for current_rule in ALL_RULES {
-            if !self.config.disabled_rules.contains(current_rule.name) {
+            let is_enabled = current_rule.enabled_by_default
+                || self.config.enabled_rules.contains(current_rule.name);
+            if is_enabled && !self.config.disabled_rules.contains(current_rule.name) {
                 checkers.push((current_rule.create)(&self.config));
             }
         }
@@ -141,14 +215,14 @@ impl GDScriptLinter {
         }
         visit_each_node(
             &root_node,
-            source_code,
+            &context,
             &mut checkers,
             &node_kind_map,
             &mut issues,
             &ignore_map,
         );
         for checker in checkers.iter_mut() {
-            let rule_issues = checker.finalize(source_code);
+            let rule_issues = checker.finalize(&context);
             for issue in rule_issues {
                 if !should_ignore_rule(&ignore_map, issue.line, &issue.rule) {
                     issues.push(issue);
@@ -170,12 +244,14 @@ impl GDScriptLinter {
     ) -> Result<bool, Box<dyn std::error::Error>> {
         let gdscript_files: Vec<&PathBuf> = input_files
             .iter()
-            .filter(|path| path.extension().is_some_and(|ext| ext == "gd"))
+            .filter(|path| {
+                path.extension().is_some_and(|ext| ext == "gd") || is_resource_file(path)
+            })
             .collect();
 
         if gdscript_files.is_empty() {
             eprintln!(
-                "Error: No GDScript files found in the arguments provided. Please provide at least one .gd file."
+                "Error: No GDScript files found in the arguments provided. Please provide at least one .gd, .tscn, or .tres file."
             );
             std::process::exit(1);
         }
@@ -189,6 +265,66 @@ impl GDScriptLinter {
         }
     }
 
+    /// Lints the content of a file. For scenes and resources, this lints each
+    /// built-in script, with line numbers relative to the whole file.
+    fn lint_file_content(
+        &mut self,
+        file_path: &Path,
+        source_code: &str,
+    ) -> Result<Vec<LintIssue>, String> {
+        if !is_resource_file(file_path) {
+            return self.lint(source_code, &file_path.to_string_lossy());
+        }
+
+        let mut issues = Vec::new();
+        for script in find_embedded_scripts(source_code) {
+            for mut issue in self.lint(&script.source, &file_path.to_string_lossy())? {
+                issue.line += script.line - 1;
+                issues.push(issue);
+            }
+        }
+        Ok(issues)
+    }
+
+    /// Applies the fixes of the issues found in the files and writes the
+    /// files that changed. Returns the number of fixes applied. We don't fix
+    /// built-in scripts of scenes and resources.
+    pub fn fix_files(
+        &mut self,
+        input_files: &[PathBuf],
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut fix_count = 0;
+        for file_path in input_files {
+            if file_path.extension().is_none_or(|ext| ext != "gd") {
+                continue;
+            }
+            let source_code = fs::read_to_string(file_path).map_err(|error| {
+                format!("Failed to read file {}: {}", file_path.display(), error)
+            })?;
+
+            // A fix can leave other code unused, like the variable that only
+            // a removed declaration used, so we lint again until nothing
+            // changes.
+            let mut fixed_code = source_code.clone();
+            loop {
+                let issues = self.lint(&fixed_code, &file_path.to_string_lossy())?;
+                let (new_code, applied) = apply_fixes(&fixed_code, &issues);
+                if applied == 0 {
+                    break;
+                }
+                fix_count += applied;
+                fixed_code = new_code;
+            }
+
+            if fixed_code != source_code {
+                fs::write(file_path, fixed_code).map_err(|error| {
+                    format!("Failed to write file {}: {}", file_path.display(), error)
+                })?;
+            }
+        }
+        Ok(fix_count)
+    }
+
     fn lint_files_pretty(
         &mut self,
         gdscript_files: &[&PathBuf],
@@ -203,7 +339,7 @@ impl GDScriptLinter {
                 format!("Failed to read file {}: {}", file_path.display(), error)
             })?;
 
-            let issues = self.lint(&source_code, &file_path.to_string_lossy())?;
+            let issues = self.lint_file_content(file_path, &source_code)?;
 
             if !issues.is_empty() {
                 has_issues = true;
@@ -277,7 +413,7 @@ impl GDScriptLinter {
                 format!("Failed to read file {}: {}", file_path.display(), error)
             })?;
 
-            let issues = self.lint(&source_code, &file_path.to_string_lossy())?;
+            let issues = self.lint_file_content(file_path, &source_code)?;
 
             for issue in issues {
                 has_issues = true;
@@ -293,7 +429,7 @@ impl GDScriptLinter {
 /// We call each rule only when we encounter an AST node it cares about.
 fn visit_each_node(
     node: &Node,
-    source_code: &str,
+    context: &LintContext,
     checkers: &mut [Box<dyn Rule>],
     node_kind_map: &HashMap<String, Vec<usize>>,
     issues: &mut Vec<LintIssue>,
@@ -301,7 +437,7 @@ fn visit_each_node(
 ) {
     if let Some(matching_rules) = node_kind_map.get(node.kind()) {
         for &rule_idx in matching_rules {
-            let rule_issues = checkers[rule_idx].check_node(node, source_code);
+            let rule_issues = checkers[rule_idx].check_node(node, context);
             for issue in rule_issues {
                 if !should_ignore_rule(ignore_map, issue.line, &issue.rule) {
                     issues.push(issue);
@@ -315,7 +451,7 @@ fn visit_each_node(
         loop {
             visit_each_node(
                 &cursor.node(),
-                source_code,
+                context,
                 checkers,
                 node_kind_map,
                 issues,
@@ -328,6 +464,32 @@ fn visit_each_node(
     }
 }
 
+/// Applies the fixes of the issues to the source code. Skips fixes that overlap
+/// a fix applied before them. Returns the fixed code and the number of fixes
+/// applied.
+pub fn apply_fixes(source_code: &str, issues: &[LintIssue]) -> (String, usize) {
+    let mut fixes: Vec<&LintFix> = issues
+        .iter()
+        .filter_map(|issue| issue.fix.as_ref())
+        .collect();
+    fixes.sort_by_key(|fix| (fix.start_byte, fix.end_byte));
+
+    let mut output = String::with_capacity(source_code.len());
+    let mut position = 0;
+    let mut applied = 0;
+    for fix in fixes {
+        if fix.start_byte < position {
+            continue;
+        }
+        output.push_str(&source_code[position..fix.start_byte]);
+        output.push_str(&fix.replacement);
+        position = fix.end_byte;
+        applied += 1;
+    }
+    output.push_str(&source_code[position..]);
+    (output, applied)
+}
+
 pub fn lint_gdscript_with_config(
     source_code: &str,
     file_path: &str,
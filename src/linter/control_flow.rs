@@ -0,0 +1,352 @@
+//! This module builds a control flow graph of a function for lint rules: the
+//! blocks of statements that run one after the other, and where the code can
+//! go after each block.
+//!
+//! The graph is lightweight: it only follows statements, not expressions, and
+//! it only knows the value of conditions that are the literals `true` and
+//! `false`. We use them to treat `while true:` as an infinite loop. Lambdas get
+//! their own graph, the graph of the function that contains them skips them.
+//!
+//! Rules build the graph of the functions they check with
+//! `ControlFlowGraph::build()`.
+use std::collections::HashSet;
+
+use tree_sitter::Node;
+
+use crate::linter::lib::get_node_text;
+
+pub type BlockId = usize;
+
+/// The block where the function starts.
+pub const ENTRY: BlockId = 0;
+/// The block after the function's end, which return statements go to.
+pub const EXIT: BlockId = 1;
+
+#[derive(Debug, Clone, Default)]
+pub struct BasicBlock<'tree> {
+    /// The statements of the block in the order of the code. Statements like
+    /// if or while are in the block before their branches.
+    pub statements: Vec<Node<'tree>>,
+    /// The blocks the code can go to after this one
+    pub successors: Vec<BlockId>,
+}
+
+/// A condition of an if, elif, or while statement whose value never changes,
+/// like `if true:`.
+#[derive(Debug, Clone, Copy)]
+pub struct ConstantCondition<'tree> {
+    pub condition: Node<'tree>,
+    pub value: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ControlFlowGraph<'tree> {
+    blocks: Vec<BasicBlock<'tree>>,
+    /// The return statements of the function and the block they're in
+    returns: Vec<(Node<'tree>, BlockId)>,
+    constant_conditions: Vec<ConstantCondition<'tree>>,
+    /// The block the code is in after the function's last statement. If it's
+    /// reachable, the function can end without a return statement.
+    end_block: BlockId,
+    reachable: Vec<bool>,
+}
+
+impl<'tree> ControlFlowGraph<'tree> {
+    /// Builds the graph of a function, constructor, or lambda node.
+    pub fn build(function: &Node<'tree>, source_code: &str) -> Self {
+        let mut builder = Builder {
+            graph: ControlFlowGraph {
+                blocks: vec![BasicBlock::default(), BasicBlock::default()],
+                returns: Vec::new(),
+                constant_conditions: Vec::new(),
+                end_block: ENTRY,
+                reachable: Vec::new(),
+            },
+            source_code,
+            current: ENTRY,
+            loops: Vec::new(),
+        };
+        if let Some(body) = find_body(function) {
+            builder.build_body(&body);
+        }
+        let end_block = builder.current;
+        builder.add_edge(end_block, EXIT);
+
+        let mut graph = builder.graph;
+        graph.end_block = end_block;
+        graph.reachable = graph.find_reachable_blocks();
+        graph
+    }
+
+    pub fn blocks(&self) -> &[BasicBlock<'tree>] {
+        &self.blocks
+    }
+
+    pub fn constant_conditions(&self) -> &[ConstantCondition<'tree>] {
+        &self.constant_conditions
+    }
+
+    pub fn is_reachable(&self, block: BlockId) -> bool {
+        self.reachable[block]
+    }
+
+    /// Returns the return statements the code can reach.
+    pub fn reachable_returns(&self) -> impl Iterator<Item = &Node<'tree>> {
+        self.returns
+            .iter()
+            .filter(|(_, block)| self.reachable[*block])
+            .map(|(statement, _)| statement)
+    }
+
+    /// Returns true if the code can reach the end of the function without a
+    /// return statement.
+    pub fn can_reach_end(&self) -> bool {
+        self.reachable[self.end_block]
+    }
+
+    /// Returns the first statement of each sequence of statements the code
+    /// can't reach. Statements inside an unreachable statement, or that follow
+    /// one, are unreachable too, but we only return the first one.
+    pub fn unreachable_statements(&self) -> Vec<Node<'tree>> {
+        let unreachable: HashSet<usize> = self
+            .blocks
+            .iter()
+            .enumerate()
+            .filter(|(id, _)| !self.reachable[*id])
+            .flat_map(|(_, block)| block.statements.iter().map(|statement| statement.id()))
+            .collect();
+
+        let mut first_statements = Vec::new();
+        for (id, block) in self.blocks.iter().enumerate() {
+            if self.reachable[id] {
+                continue;
+            }
+            for statement in &block.statements {
+                let follows_unreachable = previous_statement(statement)
+                    .is_some_and(|previous| unreachable.contains(&previous.id()));
+                let is_inside_unreachable =
+                    std::iter::successors(statement.parent(), |node| node.parent())
+                        .any(|ancestor| unreachable.contains(&ancestor.id()));
+                if !follows_unreachable && !is_inside_unreachable {
+                    first_statements.push(*statement);
+                }
+            }
+        }
+        first_statements.sort_by_key(|statement| statement.start_byte());
+        first_statements
+    }
+
+    fn find_reachable_blocks(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.blocks.len()];
+        let mut stack = vec![ENTRY];
+        while let Some(block) = stack.pop() {
+            if reachable[block] {
+                continue;
+            }
+            reachable[block] = true;
+            stack.extend(&self.blocks[block].successors);
+        }
+        reachable
+    }
+}
+
+/// Returns the value of a condition if it's always the same, like `true` or
+/// `not false`.
+pub fn get_constant_value(condition: &Node, source_code: &str) -> Option<bool> {
+    match condition.kind() {
+        "true" => Some(true),
+        "false" => Some(false),
+        "parenthesized_expression" => get_constant_value(&condition.named_child(0)?, source_code),
+        "unary_operator" => {
+            let operator = get_node_text(&condition.child(0)?, source_code);
+            let operand = condition.named_child(condition.named_child_count().checked_sub(1)?)?;
+            if operator == "not" || operator == "!" {
+                get_constant_value(&operand, source_code).map(|value| !value)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+struct Builder<'tree, 'a> {
+    graph: ControlFlowGraph<'tree>,
+    source_code: &'a str,
+    /// The block the next statement goes in
+    current: BlockId,
+    /// For each loop around the current statement, the blocks `continue` and
+    /// `break` go to
+    loops: Vec<(BlockId, BlockId)>,
+}
+
+impl<'tree> Builder<'tree, '_> {
+    fn new_block(&mut self) -> BlockId {
+        self.graph.blocks.push(BasicBlock::default());
+        self.graph.blocks.len() - 1
+    }
+
+    fn add_edge(&mut self, from: BlockId, to: BlockId) {
+        self.graph.blocks[from].successors.push(to);
+    }
+
+    /// Ends the current block with a jump. The code after the jump goes in a
+    /// new block that no other block leads to.
+    fn jump(&mut self, to: Option<BlockId>) {
+        if let Some(to) = to {
+            self.add_edge(self.current, to);
+        }
+        self.current = self.new_block();
+    }
+
+    fn build_body(&mut self, body: &Node<'tree>) {
+        let mut cursor = body.walk();
+        for statement in body.named_children(&mut cursor) {
+            if statement.kind() != "comment" {
+                self.build_statement(&statement);
+            }
+        }
+    }
+
+    fn build_statement(&mut self, statement: &Node<'tree>) {
+        self.graph.blocks[self.current].statements.push(*statement);
+        match statement.kind() {
+            "return_statement" => {
+                self.graph.returns.push((*statement, self.current));
+                self.jump(Some(EXIT));
+            }
+            "break_statement" => self.jump(self.loops.last().map(|&(_, after)| after)),
+            "continue_statement" => self.jump(self.loops.last().map(|&(header, _)| header)),
+            "if_statement" => self.build_if(statement),
+            "while_statement" | "for_statement" => self.build_loop(statement),
+            "match_statement" => self.build_match(statement),
+            _ => {}
+        }
+    }
+
+    /// Builds a branch that starts after the `from` block and joins `after`.
+    fn build_branch(&mut self, from: BlockId, body: Option<Node<'tree>>, after: BlockId) {
+        let start = self.new_block();
+        self.add_edge(from, start);
+        self.current = start;
+        if let Some(body) = body {
+            self.build_body(&body);
+        }
+        self.add_edge(self.current, after);
+    }
+
+    fn build_if(&mut self, statement: &Node<'tree>) {
+        let after = self.new_block();
+        let mut condition_block = self.current;
+        self.record_condition(statement);
+        self.build_branch(condition_block, find_body(statement), after);
+
+        let mut has_else = false;
+        let mut cursor = statement.walk();
+        for clause in statement.named_children(&mut cursor) {
+            match clause.kind() {
+                "elif_clause" => {
+                    let elif_block = self.new_block();
+                    self.add_edge(condition_block, elif_block);
+                    condition_block = elif_block;
+                    self.record_condition(&clause);
+                    self.build_branch(condition_block, find_body(&clause), after);
+                }
+                "else_clause" => {
+                    self.build_branch(condition_block, find_body(&clause), after);
+                    has_else = true;
+                }
+                _ => {}
+            }
+        }
+        if !has_else {
+            self.add_edge(condition_block, after);
+        }
+        self.current = after;
+    }
+
+    fn build_loop(&mut self, statement: &Node<'tree>) {
+        let header = self.new_block();
+        let after = self.new_block();
+        self.add_edge(self.current, header);
+        // Only `while true:` and `break` statements leave infinite loops
+        let condition = self.record_condition(statement);
+        if condition != Some(true) {
+            self.add_edge(header, after);
+        }
+
+        self.loops.push((header, after));
+        self.build_branch(header, find_body(statement), header);
+        self.loops.pop();
+        self.current = after;
+    }
+
+    fn build_match(&mut self, statement: &Node<'tree>) {
+        let before = self.current;
+        let after = self.new_block();
+        let mut is_exhaustive = false;
+
+        let mut cursor = statement.walk();
+        for match_body in statement.named_children(&mut cursor) {
+            if match_body.kind() != "match_body" {
+                continue;
+            }
+            let mut body_cursor = match_body.walk();
+            for section in match_body.named_children(&mut body_cursor) {
+                if section.kind() != "pattern_section" {
+                    continue;
+                }
+                is_exhaustive |= matches_anything(&section, self.source_code);
+                self.build_branch(before, find_body(&section), after);
+            }
+        }
+        if !is_exhaustive {
+            self.add_edge(before, after);
+        }
+        self.current = after;
+    }
+
+    /// Records the condition of an if, elif, or while statement if its value
+    /// never changes, and returns this value.
+    fn record_condition(&mut self, statement: &Node<'tree>) -> Option<bool> {
+        let condition = statement.child_by_field_name("condition")?;
+        let value = get_constant_value(&condition, self.source_code)?;
+        self.graph
+            .constant_conditions
+            .push(ConstantCondition { condition, value });
+        Some(value)
+    }
+}
+
+fn find_body<'tree>(node: &Node<'tree>) -> Option<Node<'tree>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|child| child.kind() == "body")
+}
+
+/// Returns the statement before this one in the same block, skipping comments.
+fn previous_statement<'tree>(statement: &Node<'tree>) -> Option<Node<'tree>> {
+    std::iter::successors(statement.prev_named_sibling(), |node| {
+        node.prev_named_sibling()
+    })
+    .find(|node| node.kind() != "comment")
+}
+
+/// Returns true if a branch of a match statement runs for any value, like
+/// `_:` or `var value:` without a guard.
+fn matches_anything(section: &Node, source_code: &str) -> bool {
+    let mut cursor = section.walk();
+    let patterns: Vec<Node> = section
+        .named_children(&mut cursor)
+        .filter(|child| !matches!(child.kind(), "body" | "comment"))
+        .collect();
+    if patterns
+        .iter()
+        .any(|pattern| pattern.kind() == "pattern_guard")
+    {
+        return false;
+    }
+    patterns.iter().any(|pattern| {
+        pattern.kind() == "pattern_binding" || get_node_text(pattern, source_code) == "_"
+    })
+}
@@ -1,5 +1,7 @@
 use tree_sitter::Node;
 
+use crate::linter::LintFix;
+
 pub fn get_node_text<'a>(node: &Node, source_code: &'a str) -> &'a str {
     &source_code[node.start_byte()..node.end_byte()]
 }
@@ -8,3 +10,76 @@ pub fn get_line_column(node: &Node) -> (usize, usize) {
     let start_position = node.start_position();
     (start_position.row + 1, start_position.column + 1)
 }
+
+/// Returns true if evaluating the expression may do more than produce a value,
+/// like calling a function or awaiting a signal.
+pub fn has_side_effects(node: &Node) -> bool {
+    if matches!(
+        node.kind(),
+        "call" | "attribute_call" | "base_call" | "await_expression"
+    ) {
+        return true;
+    }
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .any(|child| has_side_effects(&child))
+}
+
+/// Returns a fix that deletes a statement with its line. Returns None if the
+/// statement shares its lines with other code, or if it's the only statement
+/// of a block, as removing it would leave the block empty.
+pub fn remove_statement_fix(statement: &Node, source_code: &str) -> Option<LintFix> {
+    if let Some(parent) = statement.parent() {
+        let mut cursor = parent.walk();
+        let statement_count = parent
+            .named_children(&mut cursor)
+            .filter(|child| child.kind() != "comment")
+            .count();
+        if parent.kind() == "body" && statement_count < 2 {
+            return None;
+        }
+    }
+
+    let line_start = source_code[..statement.start_byte()]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let line_end = source_code[statement.end_byte()..]
+        .find('\n')
+        .map_or(source_code.len(), |index| statement.end_byte() + index + 1);
+    let before = &source_code[line_start..statement.start_byte()];
+    let after = &source_code[statement.end_byte()..line_end];
+    if !before.trim().is_empty() || !after.trim().is_empty() {
+        return None;
+    }
+
+    Some(LintFix {
+        start_byte: line_start,
+        end_byte: line_end,
+        replacement: String::new(),
+    })
+}
+
+/// How a variable, constant, or function parameter declares its type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeclaredType {
+    /// Like `var health: int = 100`
+    Explicit,
+    /// Like `var health := 100`
+    Inferred,
+    /// Like `var health = 100`
+    Missing,
+}
+
+/// Returns how a declaration gives its type. `node` is a variable or constant
+/// statement, or one of the children of a function's `parameters` node.
+pub fn get_declared_type(node: &Node) -> DeclaredType {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "type" => return DeclaredType::Explicit,
+            "inferred_type" | ":=" => return DeclaredType::Inferred,
+            _ => {}
+        }
+    }
+    DeclaredType::Missing
+}
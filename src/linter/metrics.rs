@@ -0,0 +1,230 @@
+//! This module measures the size and complexity of functions, for the lint
+//! rules that limit them and for the `metrics` command, and counts the members
+//! of classes for the rules that limit the size of classes.
+//!
+//! We measure each function on its own: the code of lambdas defined in a
+//! function counts towards the lambda, not the function, so we skip it.
+use tree_sitter::{Node, Parser};
+
+use crate::linter::lib::{get_line_column, get_node_text};
+use crate::reorder::{GDScriptTokenKind, classify_class_members};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionMetrics {
+    pub name: String,
+    /// Position of the function's name, or of the function itself if it has
+    /// no name
+    pub line: usize,
+    pub column: usize,
+    /// Number of statements in the function, including nested ones
+    pub statements: usize,
+    /// Number of lines from the function's signature to the end of its body
+    pub lines: usize,
+    /// One plus the number of branches: if, elif, for, while, match branches,
+    /// `and` and `or` operators, and ternary expressions
+    pub cyclomatic_complexity: usize,
+    /// The largest number of blocks nested in each other in the function
+    pub max_nesting_depth: usize,
+    pub arguments: usize,
+    pub returns: usize,
+}
+
+/// Parses the code and returns the metrics of all its functions, including
+/// the functions of inner classes, in the order of the code.
+pub fn compute_metrics(source_code: &str) -> Result<Vec<FunctionMetrics>, String> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_gdscript::LANGUAGE.into())
+        .map_err(|error| format!("Failed to set language: {}", error))?;
+    let tree = parser
+        .parse(source_code, None)
+        .ok_or("Failed to parse GDScript code")?;
+
+    let mut metrics = Vec::new();
+    collect_function_metrics(&tree.root_node(), source_code, &mut metrics);
+    Ok(metrics)
+}
+
+fn collect_function_metrics(node: &Node, source_code: &str, metrics: &mut Vec<FunctionMetrics>) {
+    if is_function(node) {
+        metrics.push(measure_function(node, source_code));
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_function_metrics(&child, source_code, metrics);
+    }
+}
+
+fn is_function(node: &Node) -> bool {
+    matches!(
+        node.kind(),
+        "function_definition" | "constructor_definition"
+    )
+}
+
+/// Measures a function or constructor definition node.
+pub fn measure_function(node: &Node, source_code: &str) -> FunctionMetrics {
+    let name_node = node.child_by_field_name("name");
+    let name = name_node
+        .map(|name| get_node_text(&name, source_code).to_string())
+        .unwrap_or_else(|| "_init".to_string());
+    let (line, column) = get_line_column(&name_node.unwrap_or(*node));
+
+    let mut cursor = node.walk();
+    let arguments = node
+        .named_children(&mut cursor)
+        .find(|child| child.kind() == "parameters")
+        .map_or(0, |parameters| {
+            let mut parameters_cursor = parameters.walk();
+            parameters
+                .named_children(&mut parameters_cursor)
+                .filter(|parameter| parameter.kind() != "comment")
+                .count()
+        });
+
+    let mut metrics = FunctionMetrics {
+        name,
+        line,
+        column,
+        statements: 0,
+        lines: node.end_position().row - node.start_position().row + 1,
+        cyclomatic_complexity: 1,
+        max_nesting_depth: 0,
+        arguments,
+        returns: 0,
+    };
+    if let Some(body) = node.child_by_field_name("body") {
+        measure_block(&body, 0, source_code, &mut metrics);
+    }
+    metrics
+}
+
+/// Counts the statements and branches of a block and of the blocks nested in
+/// it. `depth` is the number of blocks around this one in the function.
+fn measure_block(body: &Node, depth: usize, source_code: &str, metrics: &mut FunctionMetrics) {
+    metrics.max_nesting_depth = metrics.max_nesting_depth.max(depth);
+    let mut cursor = body.walk();
+    for statement in body.named_children(&mut cursor) {
+        if statement.kind() == "comment" {
+            continue;
+        }
+        metrics.statements += 1;
+        measure_node(&statement, depth, source_code, metrics);
+    }
+}
+
+fn measure_node(node: &Node, depth: usize, source_code: &str, metrics: &mut FunctionMetrics) {
+    match node.kind() {
+        "lambda" => return,
+        "if_statement"
+        | "elif_clause"
+        | "for_statement"
+        | "while_statement"
+        | "pattern_section"
+        | "conditional_expression" => metrics.cyclomatic_complexity += 1,
+        "binary_operator" => {
+            if let Some(operator) = node.child_by_field_name("op")
+                && matches!(
+                    get_node_text(&operator, source_code),
+                    "and" | "or" | "&&" | "||"
+                )
+            {
+                metrics.cyclomatic_complexity += 1;
+            }
+        }
+        "return_statement" => metrics.returns += 1,
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() == "body" {
+            measure_block(&child, depth + 1, source_code, metrics);
+        } else {
+            measure_node(&child, depth, source_code, metrics);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassMetrics {
+    /// Name of the inner class, or the class_name of the script. None for
+    /// scripts without a class_name.
+    pub name: Option<String>,
+    /// Position of the inner class's name, or the start of the file
+    pub line: usize,
+    pub column: usize,
+    /// Number of methods that don't start with an underscore, static ones
+    /// included
+    pub public_methods: usize,
+    /// Number of member variables of all kinds: regular, static, exported,
+    /// and onready
+    pub variables: usize,
+    pub inner_classes: usize,
+    pub signals: usize,
+}
+
+impl ClassMetrics {
+    /// Describes the class in lint messages, like "Class 'Player'" or
+    /// "Script" for scripts without a class_name.
+    pub fn describe(&self) -> String {
+        match &self.name {
+            Some(name) => format!("Class '{}'", name),
+            None => "Script".to_string(),
+        }
+    }
+}
+
+/// Counts the members of a class. `node` is either the `source` node of a
+/// script or a `class_definition` node. The members of inner classes don't
+/// count towards the class that contains them.
+pub fn measure_class(node: &Node, source_code: &str) -> ClassMetrics {
+    let (name, (line, column), body) = if node.kind() == "class_definition" {
+        let name_node = node.child_by_field_name("name");
+        let mut cursor = node.walk();
+        let body = node
+            .children(&mut cursor)
+            .find(|child| child.kind() == "class_body");
+        (
+            name_node.map(|name| get_node_text(&name, source_code).to_string()),
+            get_line_column(&name_node.unwrap_or(*node)),
+            body,
+        )
+    } else {
+        let mut cursor = node.walk();
+        let name = node
+            .named_children(&mut cursor)
+            .find(|child| child.kind() == "class_name_statement")
+            .and_then(|statement| statement.child_by_field_name("name"))
+            .map(|name| get_node_text(&name, source_code).to_string());
+        (name, (1, 1), Some(*node))
+    };
+
+    let mut metrics = ClassMetrics {
+        name,
+        line,
+        column,
+        public_methods: 0,
+        variables: 0,
+        inner_classes: 0,
+        signals: 0,
+    };
+    // We only count members, so we don't need the virtual methods that decide
+    // the order of methods. Members we can't classify don't count.
+    let members = body
+        .and_then(|body| classify_class_members(body, source_code, &[]).ok())
+        .unwrap_or_default();
+    for (_, token_kind) in members {
+        match token_kind {
+            GDScriptTokenKind::Method(_, _, false) => metrics.public_methods += 1,
+            GDScriptTokenKind::StaticVariable(..)
+            | GDScriptTokenKind::ExportVariable(..)
+            | GDScriptTokenKind::RegularVariable(..)
+            | GDScriptTokenKind::OnReadyVariable(..) => metrics.variables += 1,
+            GDScriptTokenKind::InnerClass(..) => metrics.inner_classes += 1,
+            GDScriptTokenKind::Signal(..) => metrics.signals += 1,
+            _ => {}
+        }
+    }
+    metrics
+}
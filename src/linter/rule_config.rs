@@ -1,7 +1,8 @@
 use crate::linter::rules::ALL_RULES;
 use std::collections::HashSet;
 
-/// Parse disabled rules from command line arguments or configuration
+/// Parse disabled rules from command line arguments or configuration. This
+/// also parses the list of rules to enable.
 pub fn parse_disabled_rules(rules_string: &str) -> HashSet<String> {
     rules_string
         .split(',')
@@ -15,6 +16,15 @@ pub fn get_all_rule_names() -> Vec<&'static str> {
     ALL_RULES.iter().map(|rule| rule.name).collect()
 }
 
+/// Get the names of the rules that only run when enabled
+pub fn get_opt_in_rule_names() -> Vec<&'static str> {
+    ALL_RULES
+        .iter()
+        .filter(|rule| !rule.enabled_by_default)
+        .map(|rule| rule.name)
+        .collect()
+}
+
 /// Validate that all provided rule names are valid
 pub fn validate_rule_names(rules: &HashSet<String>) -> Result<(), Vec<String>> {
     let valid_rules: HashSet<&str> = get_all_rule_names().into_iter().collect();
@@ -1,24 +1,66 @@
 pub mod class_name;
 pub mod comparison_with_itself;
+pub mod constant_condition;
 pub mod constant_name;
+pub mod cyclomatic_complexity;
+pub mod declaration_order;
+pub mod duplicate_dictionary_key;
+pub mod duplicate_enum_member;
+pub mod duplicate_match_pattern;
+pub mod duplicate_member;
+pub mod duplicate_parameter;
 pub mod duplicated_load;
 pub mod enum_member_name;
 pub mod enum_name;
 pub mod function_argument_name;
+pub mod function_length;
 pub mod function_name;
+pub mod incompatible_syntax;
+pub mod inconsistent_return;
+pub mod inference_on_variant;
 pub mod loop_variable_name;
+pub mod max_arguments;
+pub mod max_class_variables;
+pub mod max_file_lines;
+pub mod max_inner_classes;
 pub mod max_line_length;
+pub mod max_nesting_depth;
+pub mod max_public_methods;
+pub mod max_returns;
+pub mod max_signals;
+pub mod missing_return_type;
 pub mod no_else_return;
 pub mod private_access;
+pub mod shadowed_global_identifier;
+pub mod shadowed_variable;
+pub mod shadowed_variable_base_class;
 pub mod signal_name;
 pub mod standalone_expression;
 pub mod unnecessary_pass;
+pub mod unreachable_code;
+pub mod untyped_parameter;
+pub mod untyped_variable;
 pub mod unused_argument;
+pub mod unused_local_constant;
+pub mod unused_private_member;
+pub mod unused_variable;
 pub mod variable_name;
 
+use crate::godot_api::GodotApi;
+use crate::linter::semantic::SemanticModel;
 use crate::linter::{LintIssue, LinterConfig};
 use tree_sitter::Node;
 
+/// What rules know about the file they check when visiting nodes.
+pub struct LintContext<'a> {
+    pub source_code: &'a str,
+    /// The scopes and symbols of the file, to find what an identifier refers
+    /// to.
+    pub semantic: &'a SemanticModel,
+    /// Godot's classes and global identifiers
+    pub godot_api: &'a GodotApi,
+}
+
 pub trait Rule {
     /// Returns the list of node kinds this rule is runs on/is interested in.
     /// This is used to only call this rule on specific nodes. Return an empty
@@ -36,7 +78,7 @@ pub trait Rule {
     /// The linter calls this function for each node matching the nodes in
     /// get_target_ast_nodes when traversing the AST. This is the main method
     /// rules should use if possible to check for issue.
-    fn check_node(&mut self, _node: &Node, _source_code: &str) -> Vec<LintIssue> {
+    fn check_node(&mut self, _node: &Node, _context: &LintContext) -> Vec<LintIssue> {
         Vec::new()
     }
 
@@ -44,107 +86,308 @@ pub trait Rule {
     /// during traversal and need to process it afterwards. For example,
     /// detecting duplicated loads by collecting all load paths first, then
     /// reporting which ones are duplicated.
-    fn finalize(&mut self, _source_code: &str) -> Vec<LintIssue> {
+    fn finalize(&mut self, _context: &LintContext) -> Vec<LintIssue> {
         Vec::new()
     }
 }
 
 use class_name::ClassNameRule;
 use comparison_with_itself::ComparisonWithItselfRule;
+use constant_condition::ConstantConditionRule;
 use constant_name::ConstantNameRule;
+use cyclomatic_complexity::CyclomaticComplexityRule;
+use declaration_order::DeclarationOrderRule;
+use duplicate_dictionary_key::DuplicateDictionaryKeyRule;
+use duplicate_enum_member::DuplicateEnumMemberRule;
+use duplicate_match_pattern::DuplicateMatchPatternRule;
+use duplicate_member::DuplicateMemberRule;
+use duplicate_parameter::DuplicateParameterRule;
 use duplicated_load::DuplicatedLoadRule;
 use enum_member_name::EnumMemberNameRule;
 use enum_name::EnumNameRule;
 use function_argument_name::FunctionArgumentNameRule;
+use function_length::FunctionLengthRule;
 use function_name::FunctionNameRule;
+use incompatible_syntax::IncompatibleSyntaxRule;
+use inconsistent_return::InconsistentReturnRule;
+use inference_on_variant::InferenceOnVariantRule;
 use loop_variable_name::LoopVariableNameRule;
+use max_arguments::MaxArgumentsRule;
+use max_class_variables::MaxClassVariablesRule;
+use max_file_lines::MaxFileLinesRule;
+use max_inner_classes::MaxInnerClassesRule;
 use max_line_length::MaxLineLengthRule;
+use max_nesting_depth::MaxNestingDepthRule;
+use max_public_methods::MaxPublicMethodsRule;
+use max_returns::MaxReturnsRule;
+use max_signals::MaxSignalsRule;
+use missing_return_type::MissingReturnTypeRule;
 use no_else_return::NoElseReturnRule;
 use private_access::PrivateAccessRule;
+use shadowed_global_identifier::ShadowedGlobalIdentifierRule;
+use shadowed_variable::ShadowedVariableRule;
+use shadowed_variable_base_class::ShadowedVariableBaseClassRule;
 use signal_name::SignalNameRule;
 use standalone_expression::StandaloneExpressionRule;
 use unnecessary_pass::UnnecessaryPassRule;
+use unreachable_code::UnreachableCodeRule;
+use untyped_parameter::UntypedParameterRule;
+use untyped_variable::UntypedVariableRule;
 use unused_argument::UnusedArgumentRule;
+use unused_local_constant::UnusedLocalConstantRule;
+use unused_private_member::UnusedPrivateMemberRule;
+use unused_variable::UnusedVariableRule;
 use variable_name::VariableNameRule;
 
 pub struct RuleDefinition {
     pub name: &'static str,
+    /// Rules that are off by default only run when the config enables them,
+    /// like the static typing rules that not every project wants
+    pub enabled_by_default: bool,
     pub create: fn(&LinterConfig) -> Box<dyn Rule>,
 }
 
-/// List of all the rules available in the linter. The linter will only run
-/// these plus the ones that have not been disabled in the config.
+/// List of all the rules available in the linter. The linter runs the rules
+/// that are enabled by default and not disabled in the config, plus the ones
+/// the config enables.
 pub const ALL_RULES: &[RuleDefinition] = &[
     RuleDefinition {
         name: "duplicated-load",
+        enabled_by_default: true,
         create: |_config| {
             Box::new(DuplicatedLoadRule {
                 load_paths: std::collections::HashMap::new(),
             })
         },
     },
+    RuleDefinition {
+        name: "duplicate-member",
+        enabled_by_default: true,
+        create: |_config| Box::new(DuplicateMemberRule::default()),
+    },
+    RuleDefinition {
+        name: "duplicate-parameter",
+        enabled_by_default: true,
+        create: |_config| Box::new(DuplicateParameterRule::default()),
+    },
+    RuleDefinition {
+        name: "duplicate-dictionary-key",
+        enabled_by_default: true,
+        create: |_config| Box::new(DuplicateDictionaryKeyRule::default()),
+    },
+    RuleDefinition {
+        name: "duplicate-enum-member",
+        enabled_by_default: true,
+        create: |_config| Box::new(DuplicateEnumMemberRule::default()),
+    },
+    RuleDefinition {
+        name: "duplicate-match-pattern",
+        enabled_by_default: true,
+        create: |_config| Box::new(DuplicateMatchPatternRule::default()),
+    },
     RuleDefinition {
         name: "standalone-expression",
+        enabled_by_default: true,
         create: |_config| Box::new(StandaloneExpressionRule),
     },
     RuleDefinition {
         name: "unnecessary-pass",
+        enabled_by_default: true,
         create: |_config| Box::new(UnnecessaryPassRule),
     },
     RuleDefinition {
         name: "unused-argument",
+        enabled_by_default: true,
         create: |_config| Box::new(UnusedArgumentRule),
     },
+    RuleDefinition {
+        name: "unused-variable",
+        enabled_by_default: true,
+        create: |_config| Box::new(UnusedVariableRule),
+    },
+    RuleDefinition {
+        name: "unused-local-constant",
+        enabled_by_default: true,
+        create: |_config| Box::new(UnusedLocalConstantRule),
+    },
+    RuleDefinition {
+        name: "unused-private-member",
+        enabled_by_default: false,
+        create: |config| Box::new(UnusedPrivateMemberRule::new(config)),
+    },
+    RuleDefinition {
+        name: "shadowed-variable",
+        enabled_by_default: true,
+        create: |_config| Box::new(ShadowedVariableRule),
+    },
+    RuleDefinition {
+        name: "shadowed-variable-base-class",
+        enabled_by_default: true,
+        create: |_config| Box::new(ShadowedVariableBaseClassRule),
+    },
+    RuleDefinition {
+        name: "shadowed-global-identifier",
+        enabled_by_default: true,
+        create: |_config| Box::new(ShadowedGlobalIdentifierRule),
+    },
     RuleDefinition {
         name: "comparison-with-itself",
+        enabled_by_default: true,
         create: |_config| Box::new(ComparisonWithItselfRule),
     },
     RuleDefinition {
         name: "private-access",
+        enabled_by_default: true,
         create: |_config| Box::new(PrivateAccessRule),
     },
     RuleDefinition {
         name: "max-line-length",
+        enabled_by_default: true,
         create: |config| Box::new(MaxLineLengthRule::new(config)),
     },
     RuleDefinition {
         name: "no-else-return",
+        enabled_by_default: true,
         create: |_config| Box::new(NoElseReturnRule),
     },
+    RuleDefinition {
+        name: "unreachable-code",
+        enabled_by_default: false,
+        create: |_config| Box::new(UnreachableCodeRule),
+    },
+    RuleDefinition {
+        name: "constant-condition",
+        enabled_by_default: false,
+        create: |_config| Box::new(ConstantConditionRule),
+    },
+    RuleDefinition {
+        name: "inconsistent-return",
+        enabled_by_default: false,
+        create: |_config| Box::new(InconsistentReturnRule),
+    },
     RuleDefinition {
         name: "function-name",
+        enabled_by_default: true,
         create: |_config| Box::new(FunctionNameRule),
     },
     RuleDefinition {
         name: "class-name",
+        enabled_by_default: true,
         create: |_config| Box::new(ClassNameRule),
     },
     RuleDefinition {
         name: "signal-name",
+        enabled_by_default: true,
         create: |_config| Box::new(SignalNameRule),
     },
     RuleDefinition {
         name: "variable-name",
+        enabled_by_default: true,
         create: |_config| Box::new(VariableNameRule),
     },
     RuleDefinition {
         name: "function-argument-name",
+        enabled_by_default: true,
         create: |_config| Box::new(FunctionArgumentNameRule),
     },
     RuleDefinition {
         name: "loop-variable-name",
+        enabled_by_default: true,
         create: |_config| Box::new(LoopVariableNameRule),
     },
     RuleDefinition {
         name: "enum-name",
+        enabled_by_default: true,
         create: |_config| Box::new(EnumNameRule),
     },
     RuleDefinition {
         name: "enum-member-name",
+        enabled_by_default: true,
         create: |_config| Box::new(EnumMemberNameRule),
     },
     RuleDefinition {
         name: "constant-name",
+        enabled_by_default: true,
         create: |_config| Box::new(ConstantNameRule),
     },
+    RuleDefinition {
+        name: "incompatible-syntax",
+        enabled_by_default: true,
+        create: |config| Box::new(IncompatibleSyntaxRule::new(config.godot_version)),
+    },
+    RuleDefinition {
+        name: "declaration-order",
+        enabled_by_default: false,
+        create: |config| Box::new(DeclarationOrderRule::new(config)),
+    },
+    RuleDefinition {
+        name: "function-length",
+        enabled_by_default: false,
+        create: |config| Box::new(FunctionLengthRule::new(config)),
+    },
+    RuleDefinition {
+        name: "cyclomatic-complexity",
+        enabled_by_default: false,
+        create: |config| Box::new(CyclomaticComplexityRule::new(config)),
+    },
+    RuleDefinition {
+        name: "max-nesting-depth",
+        enabled_by_default: false,
+        create: |config| Box::new(MaxNestingDepthRule::new(config)),
+    },
+    RuleDefinition {
+        name: "max-arguments",
+        enabled_by_default: false,
+        create: |config| Box::new(MaxArgumentsRule::new(config)),
+    },
+    RuleDefinition {
+        name: "max-returns",
+        enabled_by_default: false,
+        create: |config| Box::new(MaxReturnsRule::new(config)),
+    },
+    RuleDefinition {
+        name: "max-file-lines",
+        enabled_by_default: false,
+        create: |config| Box::new(MaxFileLinesRule::new(config)),
+    },
+    RuleDefinition {
+        name: "max-public-methods",
+        enabled_by_default: false,
+        create: |config| Box::new(MaxPublicMethodsRule::new(config)),
+    },
+    RuleDefinition {
+        name: "max-class-variables",
+        enabled_by_default: false,
+        create: |config| Box::new(MaxClassVariablesRule::new(config)),
+    },
+    RuleDefinition {
+        name: "max-inner-classes",
+        enabled_by_default: false,
+        create: |config| Box::new(MaxInnerClassesRule::new(config)),
+    },
+    RuleDefinition {
+        name: "max-signals",
+        enabled_by_default: false,
+        create: |config| Box::new(MaxSignalsRule::new(config)),
+    },
+    RuleDefinition {
+        name: "untyped-parameter",
+        enabled_by_default: false,
+        create: |config| Box::new(UntypedParameterRule::new(config)),
+    },
+    RuleDefinition {
+        name: "missing-return-type",
+        enabled_by_default: false,
+        create: |_config| Box::new(MissingReturnTypeRule),
+    },
+    RuleDefinition {
+        name: "untyped-variable",
+        enabled_by_default: false,
+        create: |config| Box::new(UntypedVariableRule::new(config)),
+    },
+    RuleDefinition {
+        name: "inference-on-variant",
+        enabled_by_default: false,
+        create: |_config| Box::new(InferenceOnVariantRule),
+    },
 ];
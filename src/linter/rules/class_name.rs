@@ -1,6 +1,6 @@
 use crate::linter::lib::{get_line_column, get_node_text};
 use crate::linter::regex_patterns::PASCAL_CASE;
-use crate::linter::rules::Rule;
+use crate::linter::rules::{LintContext, Rule};
 use crate::linter::{LintIssue, LintSeverity};
 use tree_sitter::Node;
 
@@ -17,7 +17,8 @@ impl Rule for ClassNameRule {
         &["class_name_statement"]
     }
 
-    fn check_node(&mut self, node: &Node, source_code: &str) -> Vec<LintIssue> {
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
         let mut issues = Vec::new();
 
         if let Some(name_node) = node.child_by_field_name("name") {
@@ -0,0 +1,47 @@
+use crate::linter::control_flow::ControlFlowGraph;
+use crate::linter::lib::get_line_column;
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity};
+use tree_sitter::Node;
+
+/// This rule checks for if, elif, and while statements with a condition that is
+/// always true or always false, like `if true:` or `while false:`. We allow
+/// `while true:`, which is the usual way to write a loop that ends with break.
+/// It's off by default.
+pub struct ConstantConditionRule;
+
+impl Rule for ConstantConditionRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["function_definition", "constructor_definition", "lambda"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let graph = ControlFlowGraph::build(node, context.source_code);
+        let mut issues = Vec::new();
+
+        for constant in graph.constant_conditions() {
+            let Some(statement) = constant.condition.parent() else {
+                continue;
+            };
+            let keyword = match statement.kind() {
+                "while_statement" if constant.value => continue,
+                "while_statement" => "while",
+                "elif_clause" => "elif",
+                _ => "if",
+            };
+            let (line, column) = get_line_column(&constant.condition);
+            issues.push(LintIssue::new(
+                line,
+                column,
+                "constant-condition".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "The condition of this '{}' is always {}",
+                    keyword, constant.value
+                ),
+            ));
+        }
+
+        issues
+    }
+}
@@ -0,0 +1,44 @@
+use crate::linter::metrics::measure_function;
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use tree_sitter::Node;
+
+/// This rule checks for functions with too many branches. The complexity of a
+/// function is one plus the number of `if`, `elif`, `for`, and `while`
+/// statements, match branches, `and` and `or` operators, and ternary
+/// expressions in it. It's off by default.
+pub struct CyclomaticComplexityRule {
+    maximum: usize,
+}
+
+impl CyclomaticComplexityRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            maximum: config.max_cyclomatic_complexity,
+        }
+    }
+}
+
+impl Rule for CyclomaticComplexityRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["function_definition", "constructor_definition"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let metrics = measure_function(node, context.source_code);
+        if metrics.cyclomatic_complexity <= self.maximum {
+            return Vec::new();
+        }
+
+        vec![LintIssue::new(
+            metrics.line,
+            metrics.column,
+            "cyclomatic-complexity".to_string(),
+            LintSeverity::Warning,
+            format!(
+                "Function '{}' is too complex. Found a cyclomatic complexity of {}, maximum allowed is {}",
+                metrics.name, metrics.cyclomatic_complexity, self.maximum
+            ),
+        )]
+    }
+}
@@ -0,0 +1,52 @@
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use crate::reorder::find_misplaced_declarations;
+use crate::{FormatterConfig, GodotVersion};
+use tree_sitter::Node;
+
+/// Reports declarations that aren't in the order of the GDScript style guide,
+/// like the formatter's --reorder-code option would sort them. It's off by
+/// default, like reordering.
+pub struct DeclarationOrderRule {
+    godot_version: GodotVersion,
+}
+
+impl DeclarationOrderRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            godot_version: config.godot_version,
+        }
+    }
+}
+
+impl Rule for DeclarationOrderRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["source"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
+        let config = FormatterConfig {
+            godot_version: self.godot_version,
+            ..Default::default()
+        };
+        // The reorder pass reports files it can't sort as errors, in which
+        // case there's nothing to check
+        let Ok(misplaced) = find_misplaced_declarations(*node, source_code, &config) else {
+            return Vec::new();
+        };
+
+        misplaced
+            .into_iter()
+            .map(|declaration| {
+                LintIssue::new(
+                    declaration.line,
+                    1,
+                    "declaration-order".to_string(),
+                    LintSeverity::Warning,
+                    declaration.message,
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,82 @@
+use crate::linter::lib::{get_line_column, get_node_text, has_side_effects};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity};
+use std::collections::HashMap;
+use tree_sitter::Node;
+
+/// This rule checks for dictionary literals that use the same key twice, like
+/// `{"a": 1, "a": 2}`, where the second value silently replaces the first one.
+/// We collect the keys of each dictionary while visiting the tree, then report
+/// every key after the first one.
+///
+/// `{name = value}` keys are strings, so they're the same as `{"name": value}`
+/// keys. Keys with calls may give a different value each time, so we skip them.
+#[derive(Default)]
+pub struct DuplicateDictionaryKeyRule {
+    /// Maps the dictionary and its normalized keys to the text and position of
+    /// each key
+    keys: HashMap<(usize, String), Vec<(String, usize, usize)>>,
+}
+
+impl Rule for DuplicateDictionaryKeyRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["dictionary"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
+        let mut cursor = node.walk();
+        for pair in node.named_children(&mut cursor) {
+            if pair.kind() != "pair" {
+                continue;
+            }
+            let Some(key) = pair.named_child(0) else {
+                continue;
+            };
+            if has_side_effects(&key) {
+                continue;
+            }
+            let is_lua_style = key.next_sibling().is_some_and(|next| next.kind() == "=");
+            let text = get_node_text(&key, source_code);
+            let normalized_key = if is_lua_style {
+                format!("string {}", text)
+            } else if key.kind() == "string" {
+                format!("string {}", text.trim_matches(['"', '\'']))
+            } else {
+                format!("expression {}", text.split_whitespace().collect::<String>())
+            };
+
+            let (line, column) = get_line_column(&key);
+            self.keys
+                .entry((node.id(), normalized_key))
+                .or_default()
+                .push((text.to_string(), line, column));
+        }
+        Vec::new()
+    }
+
+    fn finalize(&mut self, _context: &LintContext) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+
+        for locations in self.keys.values() {
+            let Some((_, first_line, _)) = locations.first() else {
+                continue;
+            };
+            for (key, line, column) in &locations[1..] {
+                issues.push(LintIssue::new(
+                    *line,
+                    *column,
+                    "duplicate-dictionary-key".to_string(),
+                    LintSeverity::Warning,
+                    format!(
+                        "Key {} is already used in this dictionary on line {}",
+                        key, first_line
+                    ),
+                ));
+            }
+        }
+
+        self.keys.clear();
+        issues
+    }
+}
@@ -0,0 +1,121 @@
+use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity};
+use std::collections::HashMap;
+use tree_sitter::Node;
+
+/// This rule checks for enums with two members of the same name, which Godot
+/// refuses to load, and for members with the same explicit value, like
+/// `enum {A = 1, B = 1}`, which is usually a copy-paste mistake. We collect
+/// the members of each enum while visiting the tree, then report every member
+/// after the first one.
+#[derive(Default)]
+pub struct DuplicateEnumMemberRule {
+    /// Maps the enum and the name of each member to the position of the
+    /// members with this name
+    names: HashMap<(usize, String), Vec<(usize, usize)>>,
+    /// Maps the enum and each explicit value to the name and position of the
+    /// members with this value
+    values: HashMap<(usize, String), Vec<(String, usize, usize)>>,
+}
+
+impl Rule for DuplicateEnumMemberRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["enumerator_list"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
+        let mut cursor = node.walk();
+        for enumerator in node.named_children(&mut cursor) {
+            if enumerator.kind() != "enumerator" {
+                continue;
+            }
+            let Some(name_node) = enumerator.named_child(0) else {
+                continue;
+            };
+            let name = get_node_text(&name_node, source_code).to_string();
+            let (line, column) = get_line_column(&name_node);
+            self.names
+                .entry((node.id(), name.clone()))
+                .or_default()
+                .push((line, column));
+
+            if let Some(value) = enumerator.named_child(1) {
+                let value = get_node_text(&value, source_code);
+                let normalized_value = parse_integer(value)
+                    .map_or_else(|| value.to_string(), |number| number.to_string());
+                self.values
+                    .entry((node.id(), normalized_value))
+                    .or_default()
+                    .push((name, line, column));
+            }
+        }
+        Vec::new()
+    }
+
+    fn finalize(&mut self, _context: &LintContext) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+
+        for ((_, name), locations) in &self.names {
+            let Some((first_line, _)) = locations.first() else {
+                continue;
+            };
+            for (line, column) in &locations[1..] {
+                issues.push(LintIssue::new(
+                    *line,
+                    *column,
+                    "duplicate-enum-member".to_string(),
+                    LintSeverity::Error,
+                    format!(
+                        "Enum member '{}' is already declared on line {}",
+                        name, first_line
+                    ),
+                ));
+            }
+        }
+        for members in self.values.values() {
+            let Some((first_name, first_line, _)) = members.first() else {
+                continue;
+            };
+            for (name, line, column) in &members[1..] {
+                // Members with the same name are already reported
+                if name == first_name {
+                    continue;
+                }
+                issues.push(LintIssue::new(
+                    *line,
+                    *column,
+                    "duplicate-enum-member".to_string(),
+                    LintSeverity::Warning,
+                    format!(
+                        "Enum member '{}' has the same value as '{}' declared on line {}",
+                        name, first_name, first_line
+                    ),
+                ));
+            }
+        }
+
+        self.names.clear();
+        self.values.clear();
+        issues
+    }
+}
+
+/// Parses integer literals like `10`, `-1`, `0xff`, or `1_000`, so we can
+/// compare values written differently.
+fn parse_integer(text: &str) -> Option<i64> {
+    let text = text.replace('_', "");
+    let (is_negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits.trim_start()),
+        None => (false, text.as_str()),
+    };
+    let number = if let Some(hex) = digits.strip_prefix("0x") {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if let Some(binary) = digits.strip_prefix("0b") {
+        i64::from_str_radix(binary, 2).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    Some(if is_negative { -number } else { number })
+}
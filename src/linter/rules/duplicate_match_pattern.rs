@@ -0,0 +1,79 @@
+use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity};
+use std::collections::HashMap;
+use tree_sitter::Node;
+
+/// This rule checks for match statements that test the same pattern twice.
+/// The branch of the second pattern never runs for this pattern. We collect
+/// the patterns of each match statement while visiting the tree, then report
+/// every pattern after the first one. Branches with a guard like `when x > 0`
+/// may not run, so their patterns don't count.
+#[derive(Default)]
+pub struct DuplicateMatchPatternRule {
+    /// Maps the match statement and the text of each pattern without spaces to
+    /// the text and position of the patterns
+    patterns: HashMap<(usize, String), Vec<(String, usize, usize)>>,
+}
+
+impl Rule for DuplicateMatchPatternRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["match_body"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
+        let mut cursor = node.walk();
+        for section in node.named_children(&mut cursor) {
+            if section.kind() != "pattern_section" {
+                continue;
+            }
+            let mut section_cursor = section.walk();
+            let patterns: Vec<Node> = section
+                .named_children(&mut section_cursor)
+                .filter(|child| !matches!(child.kind(), "body" | "comment"))
+                .collect();
+            if patterns
+                .iter()
+                .any(|pattern| pattern.kind() == "pattern_guard")
+            {
+                continue;
+            }
+
+            for pattern in patterns {
+                let text = get_node_text(&pattern, source_code);
+                let (line, column) = get_line_column(&pattern);
+                self.patterns
+                    .entry((node.id(), text.split_whitespace().collect()))
+                    .or_default()
+                    .push((text.to_string(), line, column));
+            }
+        }
+        Vec::new()
+    }
+
+    fn finalize(&mut self, _context: &LintContext) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+
+        for locations in self.patterns.values() {
+            let Some((_, first_line, _)) = locations.first() else {
+                continue;
+            };
+            for (pattern, line, column) in &locations[1..] {
+                issues.push(LintIssue::new(
+                    *line,
+                    *column,
+                    "duplicate-match-pattern".to_string(),
+                    LintSeverity::Warning,
+                    format!(
+                        "Pattern '{}' is already matched on line {}",
+                        pattern, first_line
+                    ),
+                ));
+            }
+        }
+
+        self.patterns.clear();
+        issues
+    }
+}
@@ -0,0 +1,120 @@
+use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity};
+use std::collections::HashMap;
+use tree_sitter::Node;
+
+/// This rule checks for members of a class with the same name, like a variable
+/// and a function, which Godot refuses to load. We collect the members of each
+/// class while visiting the tree, then report every member declared after the
+/// first one with its name.
+#[derive(Default)]
+pub struct DuplicateMemberRule {
+    /// Maps the class body and the name of each member to the kind and
+    /// position of its declarations
+    members: HashMap<(usize, String), Vec<(&'static str, usize, usize)>>,
+}
+
+impl DuplicateMemberRule {
+    fn add_member(
+        &mut self,
+        class_body: &Node,
+        name_node: &Node,
+        kind: &'static str,
+        source_code: &str,
+    ) {
+        let name = get_node_text(name_node, source_code).to_string();
+        let (line, column) = get_line_column(name_node);
+        self.members
+            .entry((class_body.id(), name))
+            .or_default()
+            .push((kind, line, column));
+    }
+}
+
+impl Rule for DuplicateMemberRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &[
+            "variable_statement",
+            "export_variable_statement",
+            "onready_variable_statement",
+            "const_statement",
+            "signal_statement",
+            "function_definition",
+            "class_definition",
+            "enum_definition",
+        ]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
+        let Some(class_body) = node
+            .parent()
+            .filter(|parent| matches!(parent.kind(), "source" | "class_body"))
+        else {
+            return Vec::new();
+        };
+
+        let kind = match node.kind() {
+            "const_statement" => "constant",
+            "signal_statement" => "signal",
+            "function_definition" => "function",
+            "class_definition" => "class",
+            "enum_definition" => "enum",
+            _ => "variable",
+        };
+        if let Some(name_node) = node.child_by_field_name("name") {
+            self.add_member(&class_body, &name_node, kind, source_code);
+        } else if node.kind() == "enum_definition" {
+            // The members of unnamed enums are constants of the class
+            let mut cursor = node.walk();
+            for list in node.named_children(&mut cursor) {
+                let mut list_cursor = list.walk();
+                for enumerator in list.named_children(&mut list_cursor) {
+                    if enumerator.kind() == "enumerator"
+                        && let Some(name_node) = enumerator.named_child(0)
+                    {
+                        self.add_member(&class_body, &name_node, "enum member", source_code);
+                    }
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    fn finalize(&mut self, _context: &LintContext) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+
+        for ((_, name), declarations) in &self.members {
+            let Some((first_kind, first_line, _)) = declarations.first() else {
+                continue;
+            };
+            for (kind, line, column) in &declarations[1..] {
+                issues.push(LintIssue::new(
+                    *line,
+                    *column,
+                    "duplicate-member".to_string(),
+                    LintSeverity::Error,
+                    format!(
+                        "{} '{}' has the same name as the {} declared on line {}",
+                        capitalize(kind),
+                        name,
+                        first_kind,
+                        first_line
+                    ),
+                ));
+            }
+        }
+
+        self.members.clear();
+        issues
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
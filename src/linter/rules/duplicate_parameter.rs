@@ -0,0 +1,67 @@
+use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity};
+use std::collections::HashMap;
+use tree_sitter::Node;
+
+/// This rule checks for functions and lambdas with two arguments of the same
+/// name, which Godot refuses to load. We collect the arguments of each function
+/// while visiting the tree, then report every argument after the first one
+/// with its name.
+#[derive(Default)]
+pub struct DuplicateParameterRule {
+    /// Maps the parameters node and the name of each argument to the position
+    /// of the arguments with this name
+    parameters: HashMap<(usize, String), Vec<(usize, usize)>>,
+}
+
+impl Rule for DuplicateParameterRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["parameters"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let mut cursor = node.walk();
+        for parameter in node.named_children(&mut cursor) {
+            let name_node = if parameter.kind() == "identifier" {
+                Some(parameter)
+            } else {
+                parameter.named_child(0)
+            };
+            let Some(name_node) = name_node.filter(|name| name.kind() == "identifier") else {
+                continue;
+            };
+            let name = get_node_text(&name_node, context.source_code).to_string();
+            self.parameters
+                .entry((node.id(), name))
+                .or_default()
+                .push(get_line_column(&name_node));
+        }
+        Vec::new()
+    }
+
+    fn finalize(&mut self, _context: &LintContext) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+
+        for ((_, name), locations) in &self.parameters {
+            let Some((first_line, _)) = locations.first() else {
+                continue;
+            };
+            for (line, column) in &locations[1..] {
+                issues.push(LintIssue::new(
+                    *line,
+                    *column,
+                    "duplicate-parameter".to_string(),
+                    LintSeverity::Error,
+                    format!(
+                        "Function argument '{}' is already declared on line {}",
+                        name, first_line
+                    ),
+                ));
+            }
+        }
+
+        self.parameters.clear();
+        issues
+    }
+}
@@ -1,5 +1,5 @@
 use crate::linter::lib::{get_line_column, get_node_text};
-use crate::linter::rules::Rule;
+use crate::linter::rules::{LintContext, Rule};
 use crate::linter::{LintIssue, LintSeverity};
 use std::collections::HashMap;
 use tree_sitter::Node;
@@ -13,7 +13,8 @@ impl Rule for DuplicatedLoadRule {
         &["call"]
     }
 
-    fn check_node(&mut self, node: &Node, source_code: &str) -> Vec<LintIssue> {
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
         if let Some(function_node) = node.child(0) {
             let function_name = get_node_text(&function_node, source_code);
             if (function_name == "load" || function_name == "preload")
@@ -41,7 +42,7 @@ impl Rule for DuplicatedLoadRule {
         Vec::new()
     }
 
-    fn finalize(&mut self, _source_code: &str) -> Vec<LintIssue> {
+    fn finalize(&mut self, _context: &LintContext) -> Vec<LintIssue> {
         let mut issues = Vec::new();
 
         for (path, locations) in &self.load_paths {
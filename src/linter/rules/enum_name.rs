@@ -1,6 +1,6 @@
 use crate::linter::lib::{get_line_column, get_node_text};
 use crate::linter::regex_patterns::PASCAL_CASE;
-use crate::linter::rules::Rule;
+use crate::linter::rules::{LintContext, Rule};
 use crate::linter::{LintIssue, LintSeverity};
 use tree_sitter::Node;
 pub struct EnumNameRule;
@@ -16,7 +16,8 @@ impl Rule for EnumNameRule {
         &["enum_definition"]
     }
 
-    fn check_node(&mut self, node: &Node, source_code: &str) -> Vec<LintIssue> {
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
         let mut issues = Vec::new();
 
         // Check enum name
@@ -0,0 +1,59 @@
+use crate::linter::metrics::measure_function;
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use tree_sitter::Node;
+
+/// This rule checks for functions with too many lines or too many statements.
+/// Statements in nested blocks count, but not the ones in lambdas. It's off
+/// by default.
+pub struct FunctionLengthRule {
+    max_lines: usize,
+    max_statements: usize,
+}
+
+impl FunctionLengthRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            max_lines: config.max_function_lines,
+            max_statements: config.max_function_statements,
+        }
+    }
+}
+
+impl Rule for FunctionLengthRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["function_definition", "constructor_definition"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let metrics = measure_function(node, context.source_code);
+        let mut issues = Vec::new();
+
+        if metrics.lines > self.max_lines {
+            issues.push(LintIssue::new(
+                metrics.line,
+                metrics.column,
+                "function-length".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "Function '{}' is too long. Found {} lines, maximum allowed is {}",
+                    metrics.name, metrics.lines, self.max_lines
+                ),
+            ));
+        }
+        if metrics.statements > self.max_statements {
+            issues.push(LintIssue::new(
+                metrics.line,
+                metrics.column,
+                "function-length".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "Function '{}' is too long. Found {} statements, maximum allowed is {}",
+                    metrics.name, metrics.statements, self.max_statements
+                ),
+            ));
+        }
+
+        issues
+    }
+}
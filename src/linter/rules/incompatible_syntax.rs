@@ -0,0 +1,110 @@
+use crate::GodotVersion;
+use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity};
+use tree_sitter::Node;
+
+/// Flags syntax that doesn't exist in the targeted major version of Godot, like
+/// `onready var` in a Godot 4 script or annotations in a Godot 3 script.
+pub struct IncompatibleSyntaxRule {
+    godot_version: GodotVersion,
+}
+
+impl IncompatibleSyntaxRule {
+    pub fn new(godot_version: GodotVersion) -> Self {
+        Self { godot_version }
+    }
+}
+
+impl Rule for IncompatibleSyntaxRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        match self.godot_version {
+            GodotVersion::Godot3 => &["annotation", "setget", "lambda", "await_expression"],
+            GodotVersion::Godot4 => &[
+                "tool_statement",
+                "export_variable_statement",
+                "onready_variable_statement",
+                "setget",
+                "remote_keyword",
+                "call",
+            ],
+        }
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
+        let message = match self.godot_version {
+            GodotVersion::Godot3 => godot_4_syntax_message(node, source_code),
+            GodotVersion::Godot4 => godot_3_syntax_message(node, source_code),
+        };
+
+        let Some(message) = message else {
+            return Vec::new();
+        };
+        let (line, column) = get_line_column(node);
+        vec![LintIssue::new(
+            line,
+            column,
+            "incompatible-syntax".to_string(),
+            LintSeverity::Error,
+            message,
+        )]
+    }
+}
+
+/// Returns a message explaining how to replace the node if it uses Godot 3
+/// syntax.
+fn godot_3_syntax_message(node: &Node, source_code: &str) -> Option<String> {
+    let message = match node.kind() {
+        "tool_statement" => "'tool' is Godot 3 syntax, use the @tool annotation instead",
+        "export_variable_statement" => {
+            "'export var' is Godot 3 syntax, use the @export annotations instead"
+        }
+        "onready_variable_statement" => {
+            "'onready var' is Godot 3 syntax, use the @onready annotation instead"
+        }
+        "setget" if get_node_text(node, source_code).starts_with("setget") => {
+            "'setget' is Godot 3 syntax, define the property's set and get functions after a colon instead"
+        }
+        "remote_keyword" => {
+            return Some(format!(
+                "'{}' is Godot 3 syntax, use the @rpc annotation instead",
+                get_node_text(node, source_code)
+            ));
+        }
+        "call" if is_yield_call(node, source_code) => {
+            "'yield' is Godot 3 syntax, use the await keyword instead"
+        }
+        _ => return None,
+    };
+    Some(message.to_string())
+}
+
+/// Returns a message explaining that the node doesn't exist in Godot 3, if it
+/// uses Godot 4 syntax.
+fn godot_4_syntax_message(node: &Node, source_code: &str) -> Option<String> {
+    let message = match node.kind() {
+        "annotation" => {
+            return Some(format!(
+                "Annotations like '{}' don't exist in Godot 3, use keywords like export, onready, or tool instead",
+                get_node_text(node, source_code)
+                    .split(['(', ' '])
+                    .next()
+                    .unwrap_or_default()
+            ));
+        }
+        "setget" if get_node_text(node, source_code).starts_with(':') => {
+            "Property set and get functions don't exist in Godot 3, use setget instead"
+        }
+        "lambda" => "Lambda functions don't exist in Godot 3",
+        "await_expression" => "'await' doesn't exist in Godot 3, use yield() instead",
+        _ => return None,
+    };
+    Some(message.to_string())
+}
+
+fn is_yield_call(node: &Node, source_code: &str) -> bool {
+    node.child(0).is_some_and(|function| {
+        function.kind() == "identifier" && get_node_text(&function, source_code) == "yield"
+    })
+}
@@ -0,0 +1,83 @@
+use crate::linter::control_flow::ControlFlowGraph;
+use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity};
+use tree_sitter::Node;
+
+/// This rule checks for functions that return a value on some paths but not on
+/// others: return statements without a value, or reaching the end of the
+/// function without a return statement. Functions declared with `-> void`
+/// can't return values, so we skip them. It's off by default.
+pub struct InconsistentReturnRule;
+
+impl Rule for InconsistentReturnRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["function_definition", "lambda"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
+        if returns_void(node, source_code) {
+            return Vec::new();
+        }
+
+        let graph = ControlFlowGraph::build(node, source_code);
+        let (with_value, without_value): (Vec<&Node>, Vec<&Node>) = graph
+            .reachable_returns()
+            .partition(|statement| has_value(statement));
+        if with_value.is_empty() {
+            return Vec::new();
+        }
+
+        let name = node.child_by_field_name("name");
+        let description = match name {
+            Some(name) => format!("Function '{}'", get_node_text(&name, source_code)),
+            None => "This lambda".to_string(),
+        };
+        let mut issues = Vec::new();
+        if graph.can_reach_end() {
+            let (line, column) = get_line_column(&name.unwrap_or(*node));
+            issues.push(LintIssue::new(
+                line,
+                column,
+                "inconsistent-return".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "{} returns a value on some paths, but can reach its end without returning one",
+                    description
+                ),
+            ));
+        }
+        for statement in without_value {
+            let (line, column) = get_line_column(statement);
+            issues.push(LintIssue::new(
+                line,
+                column,
+                "inconsistent-return".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "{} returns a value elsewhere, but this return statement has no value",
+                    description
+                ),
+            ));
+        }
+
+        issues
+    }
+}
+
+fn has_value(return_statement: &Node) -> bool {
+    let mut cursor = return_statement.walk();
+    return_statement
+        .named_children(&mut cursor)
+        .any(|child| child.kind() != "comment")
+}
+
+/// Returns true if the function is declared with `-> void`.
+fn returns_void(function: &Node, source_code: &str) -> bool {
+    let mut cursor = function.walk();
+    let children: Vec<Node> = function.children(&mut cursor).collect();
+    children
+        .windows(2)
+        .any(|pair| pair[0].kind() == "->" && get_node_text(&pair[1], source_code) == "void")
+}
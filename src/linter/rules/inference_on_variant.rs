@@ -0,0 +1,151 @@
+use crate::linter::lib::{DeclaredType, get_declared_type, get_line_column, get_node_text};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::semantic::SymbolKind;
+use crate::linter::{LintIssue, LintSeverity};
+use tree_sitter::Node;
+
+/// Methods that return a Variant or a plain Node, so variables inferring their
+/// type from them don't get a useful type.
+const UNTYPED_METHODS: &[&str] = &[
+    "call",
+    "callv",
+    "get",
+    "get_meta",
+    "get_node",
+    "get_node_or_null",
+];
+
+/// This rule checks for variables that infer their type with `:=` from a value
+/// without a specific type, like Godot's INFERENCE_ON_VARIANT warning. It's off
+/// by default.
+///
+/// We don't know the type of most expressions, so we only report values we know
+/// are Variants or plain nodes: node paths like `$Sprite2D`, calls to methods
+/// like `get_node()`, and subscripts of variables declared in the file that are
+/// untyped, or untyped arrays or dictionaries.
+pub struct InferenceOnVariantRule;
+
+impl Rule for InferenceOnVariantRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["variable_statement"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        if get_declared_type(node) != DeclaredType::Inferred {
+            return Vec::new();
+        }
+        let (Some(name_node), Some(value)) = (
+            node.child_by_field_name("name"),
+            node.child_by_field_name("value"),
+        ) else {
+            return Vec::new();
+        };
+        let Some(source) = describe_untyped_value(&value, context) else {
+            return Vec::new();
+        };
+
+        let (line, column) = get_line_column(&name_node);
+        vec![LintIssue::new(
+            line,
+            column,
+            "inference-on-variant".to_string(),
+            LintSeverity::Warning,
+            format!(
+                "Variable '{}' infers its type from {}, which has no specific type. Declare its type explicitly",
+                get_node_text(&name_node, context.source_code),
+                source
+            ),
+        )]
+    }
+}
+
+/// Returns a description of the value if we know it has no specific type.
+fn describe_untyped_value(value: &Node, context: &LintContext) -> Option<String> {
+    let source_code = context.source_code;
+    match value.kind() {
+        "get_node" => Some("a node path".to_string()),
+        "call" | "attribute" => {
+            let call = if value.kind() == "call" {
+                *value
+            } else {
+                value
+                    .named_child(value.named_child_count().checked_sub(1)?)
+                    .filter(|last| last.kind() == "attribute_call")?
+            };
+            let method_name = get_node_text(&call.child(0)?, source_code);
+            UNTYPED_METHODS
+                .contains(&method_name)
+                .then(|| format!("a call to {}()", method_name))
+        }
+        "subscript" => {
+            let base = value.named_child(0)?;
+            if base.kind() != "identifier" || !is_untyped_container(&base, context) {
+                return None;
+            }
+            Some(format!(
+                "a subscript of '{}'",
+                get_node_text(&base, source_code)
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if the identifier refers to a variable or argument declared in
+/// the file that is untyped, or an array or dictionary without element types.
+fn is_untyped_container(identifier: &Node, context: &LintContext) -> bool {
+    let Some(symbol) = context
+        .semantic
+        .resolve(identifier)
+        .map(|symbol| context.semantic.symbol(symbol))
+        .filter(|symbol| matches!(symbol.kind, SymbolKind::Variable | SymbolKind::Parameter))
+    else {
+        return false;
+    };
+    let start_byte = symbol.start_byte;
+    let mut root = *identifier;
+    while let Some(parent) = root.parent() {
+        root = parent;
+    }
+    let Some(name_node) = root.descendant_for_byte_range(start_byte, start_byte) else {
+        return false;
+    };
+    let Some(declaration) =
+        std::iter::successors(Some(name_node), |node| node.parent()).find(|node| {
+            matches!(
+                node.kind(),
+                "variable_statement"
+                    | "parameters"
+                    | "default_parameter"
+                    | "typed_parameter"
+                    | "typed_default_parameter"
+            )
+        })
+    else {
+        return false;
+    };
+    // Untyped arguments without a default value are identifiers in the
+    // function's parameters
+    if declaration.kind() == "parameters" {
+        return true;
+    }
+
+    match get_declared_type(&declaration) {
+        DeclaredType::Missing => true,
+        DeclaredType::Explicit => {
+            let mut cursor = declaration.walk();
+            declaration
+                .children(&mut cursor)
+                .find(|child| child.kind() == "type")
+                .is_some_and(|type_node| {
+                    matches!(
+                        get_node_text(&type_node, context.source_code),
+                        "Array" | "Dictionary"
+                    )
+                })
+        }
+        DeclaredType::Inferred => declaration
+            .child_by_field_name("value")
+            .is_some_and(|value| matches!(value.kind(), "array" | "dictionary")),
+    }
+}
@@ -0,0 +1,42 @@
+use crate::linter::metrics::measure_function;
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use tree_sitter::Node;
+
+/// This rule checks for functions that take too many arguments. It's off by
+/// default.
+pub struct MaxArgumentsRule {
+    maximum: usize,
+}
+
+impl MaxArgumentsRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            maximum: config.max_arguments,
+        }
+    }
+}
+
+impl Rule for MaxArgumentsRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["function_definition", "constructor_definition"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let metrics = measure_function(node, context.source_code);
+        if metrics.arguments <= self.maximum {
+            return Vec::new();
+        }
+
+        vec![LintIssue::new(
+            metrics.line,
+            metrics.column,
+            "max-arguments".to_string(),
+            LintSeverity::Warning,
+            format!(
+                "Function '{}' has too many arguments. Found {}, maximum allowed is {}",
+                metrics.name, metrics.arguments, self.maximum
+            ),
+        )]
+    }
+}
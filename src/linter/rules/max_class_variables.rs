@@ -0,0 +1,44 @@
+use crate::linter::metrics::measure_class;
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use tree_sitter::Node;
+
+/// This rule checks for classes with too many member variables. Static, exported,
+/// and onready variables count too. It's off by default.
+pub struct MaxClassVariablesRule {
+    max_variables: usize,
+}
+
+impl MaxClassVariablesRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            max_variables: config.max_class_variables,
+        }
+    }
+}
+
+impl Rule for MaxClassVariablesRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["source", "class_definition"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let metrics = measure_class(node, context.source_code);
+        if metrics.variables <= self.max_variables {
+            return Vec::new();
+        }
+
+        vec![LintIssue::new(
+            metrics.line,
+            metrics.column,
+            "max-class-variables".to_string(),
+            LintSeverity::Warning,
+            format!(
+                "{} has too many member variables. Found {}, maximum allowed is {}",
+                metrics.describe(),
+                metrics.variables,
+                self.max_variables
+            ),
+        )]
+    }
+}
@@ -0,0 +1,36 @@
+use crate::linter::rules::Rule;
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+
+/// This rule checks for files with too many lines. We report the first line
+/// past the limit. It's off by default.
+pub struct MaxFileLinesRule {
+    max_lines: usize,
+}
+
+impl MaxFileLinesRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            max_lines: config.max_file_lines,
+        }
+    }
+}
+
+impl Rule for MaxFileLinesRule {
+    fn check_source(&mut self, source_code: &str) -> Vec<LintIssue> {
+        let line_count = source_code.lines().count();
+        if line_count <= self.max_lines {
+            return Vec::new();
+        }
+
+        vec![LintIssue::new(
+            self.max_lines + 1,
+            1,
+            "max-file-lines".to_string(),
+            LintSeverity::Warning,
+            format!(
+                "File is too long. Found {} lines, maximum allowed is {}",
+                line_count, self.max_lines
+            ),
+        )]
+    }
+}
@@ -0,0 +1,44 @@
+use crate::linter::metrics::measure_class;
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use tree_sitter::Node;
+
+/// This rule checks for classes that define too many inner classes. Consider
+/// moving some of them to their own file. It's off by default.
+pub struct MaxInnerClassesRule {
+    max_inner_classes: usize,
+}
+
+impl MaxInnerClassesRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            max_inner_classes: config.max_inner_classes,
+        }
+    }
+}
+
+impl Rule for MaxInnerClassesRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["source", "class_definition"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let metrics = measure_class(node, context.source_code);
+        if metrics.inner_classes <= self.max_inner_classes {
+            return Vec::new();
+        }
+
+        vec![LintIssue::new(
+            metrics.line,
+            metrics.column,
+            "max-inner-classes".to_string(),
+            LintSeverity::Warning,
+            format!(
+                "{} has too many inner classes. Found {}, maximum allowed is {}",
+                metrics.describe(),
+                metrics.inner_classes,
+                self.max_inner_classes
+            ),
+        )]
+    }
+}
@@ -0,0 +1,42 @@
+use crate::linter::metrics::measure_function;
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use tree_sitter::Node;
+
+/// This rule checks for functions with too many blocks nested in each other,
+/// like an if statement in a loop in another loop. It's off by default.
+pub struct MaxNestingDepthRule {
+    maximum: usize,
+}
+
+impl MaxNestingDepthRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            maximum: config.max_nesting_depth,
+        }
+    }
+}
+
+impl Rule for MaxNestingDepthRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["function_definition", "constructor_definition"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let metrics = measure_function(node, context.source_code);
+        if metrics.max_nesting_depth <= self.maximum {
+            return Vec::new();
+        }
+
+        vec![LintIssue::new(
+            metrics.line,
+            metrics.column,
+            "max-nesting-depth".to_string(),
+            LintSeverity::Warning,
+            format!(
+                "Function '{}' is too deeply nested. Found {} nested blocks, maximum allowed is {}",
+                metrics.name, metrics.max_nesting_depth, self.maximum
+            ),
+        )]
+    }
+}
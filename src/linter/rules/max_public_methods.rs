@@ -0,0 +1,45 @@
+use crate::linter::metrics::measure_class;
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use tree_sitter::Node;
+
+/// This rule checks for classes with too many public methods, which often do too
+/// much. Methods that start with an underscore don't count. It's off by
+/// default.
+pub struct MaxPublicMethodsRule {
+    max_public_methods: usize,
+}
+
+impl MaxPublicMethodsRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            max_public_methods: config.max_public_methods,
+        }
+    }
+}
+
+impl Rule for MaxPublicMethodsRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["source", "class_definition"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let metrics = measure_class(node, context.source_code);
+        if metrics.public_methods <= self.max_public_methods {
+            return Vec::new();
+        }
+
+        vec![LintIssue::new(
+            metrics.line,
+            metrics.column,
+            "max-public-methods".to_string(),
+            LintSeverity::Warning,
+            format!(
+                "{} has too many public methods. Found {}, maximum allowed is {}",
+                metrics.describe(),
+                metrics.public_methods,
+                self.max_public_methods
+            ),
+        )]
+    }
+}
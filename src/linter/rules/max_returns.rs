@@ -0,0 +1,42 @@
+use crate::linter::metrics::measure_function;
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use tree_sitter::Node;
+
+/// This rule checks for functions with too many return statements, which make
+/// it hard to follow what a function returns. It's off by default.
+pub struct MaxReturnsRule {
+    maximum: usize,
+}
+
+impl MaxReturnsRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            maximum: config.max_returns,
+        }
+    }
+}
+
+impl Rule for MaxReturnsRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["function_definition", "constructor_definition"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let metrics = measure_function(node, context.source_code);
+        if metrics.returns <= self.maximum {
+            return Vec::new();
+        }
+
+        vec![LintIssue::new(
+            metrics.line,
+            metrics.column,
+            "max-returns".to_string(),
+            LintSeverity::Warning,
+            format!(
+                "Function '{}' has too many return statements. Found {}, maximum allowed is {}",
+                metrics.name, metrics.returns, self.maximum
+            ),
+        )]
+    }
+}
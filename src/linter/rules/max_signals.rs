@@ -0,0 +1,43 @@
+use crate::linter::metrics::measure_class;
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use tree_sitter::Node;
+
+/// This rule checks for classes with too many signals. It's off by default.
+pub struct MaxSignalsRule {
+    max_signals: usize,
+}
+
+impl MaxSignalsRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            max_signals: config.max_signals,
+        }
+    }
+}
+
+impl Rule for MaxSignalsRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["source", "class_definition"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let metrics = measure_class(node, context.source_code);
+        if metrics.signals <= self.max_signals {
+            return Vec::new();
+        }
+
+        vec![LintIssue::new(
+            metrics.line,
+            metrics.column,
+            "max-signals".to_string(),
+            LintSeverity::Warning,
+            format!(
+                "{} has too many signals. Found {}, maximum allowed is {}",
+                metrics.describe(),
+                metrics.signals,
+                self.max_signals
+            ),
+        )]
+    }
+}
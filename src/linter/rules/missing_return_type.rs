@@ -0,0 +1,39 @@
+use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity};
+use tree_sitter::Node;
+
+/// This rule checks for functions without a return type like `-> void`, like
+/// Godot's UNTYPED_DECLARATION warning. It's off by default. Constructors and
+/// lambdas don't need one.
+pub struct MissingReturnTypeRule;
+
+impl Rule for MissingReturnTypeRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["function_definition"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let Some(name_node) = node.child_by_field_name("name") else {
+            return Vec::new();
+        };
+        let name = get_node_text(&name_node, context.source_code);
+        let mut cursor = node.walk();
+        let has_return_type = node.children(&mut cursor).any(|child| child.kind() == "->");
+        if has_return_type || name == "_init" {
+            return Vec::new();
+        }
+
+        let (line, column) = get_line_column(&name_node);
+        vec![LintIssue::new(
+            line,
+            column,
+            "missing-return-type".to_string(),
+            LintSeverity::Warning,
+            format!(
+                "Function '{}' has no static return type. Add one like '-> void'",
+                name
+            ),
+        )]
+    }
+}
@@ -1,5 +1,5 @@
 use crate::linter::lib::{get_line_column, get_node_text};
-use crate::linter::rules::Rule;
+use crate::linter::rules::{LintContext, Rule};
 use crate::linter::{LintIssue, LintSeverity};
 use tree_sitter::Node;
 pub struct PrivateAccessRule;
@@ -9,7 +9,8 @@ impl Rule for PrivateAccessRule {
         &["attribute"]
     }
 
-    fn check_node(&mut self, node: &Node, source_code: &str) -> Vec<LintIssue> {
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
         let mut issues = Vec::new();
 
         let mut attr_cursor = node.walk();
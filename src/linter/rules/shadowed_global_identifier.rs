@@ -0,0 +1,41 @@
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity};
+use tree_sitter::Node;
+
+/// This rule checks for local variables, constants, function arguments, and
+/// loop variables with the same name as a global function, type, class, or
+/// singleton of Godot, like `print` or `Vector2`. It's the equivalent of
+/// Godot's SHADOWED_GLOBAL_IDENTIFIER warning.
+pub struct ShadowedGlobalIdentifierRule;
+
+impl Rule for ShadowedGlobalIdentifierRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["source"]
+    }
+
+    fn check_node(&mut self, _node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let semantic = context.semantic;
+        let mut issues = Vec::new();
+
+        for (symbol_id, symbol) in semantic.symbols().iter().enumerate() {
+            let Some(description) = semantic.describe_local(symbol_id) else {
+                continue;
+            };
+            if !context.godot_api.is_global_identifier(&symbol.name) {
+                continue;
+            }
+            issues.push(LintIssue::new(
+                symbol.line,
+                symbol.column,
+                "shadowed-global-identifier".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "{} '{}' shadows a global identifier of Godot",
+                    description, symbol.name
+                ),
+            ));
+        }
+
+        issues
+    }
+}
@@ -0,0 +1,45 @@
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::semantic::ScopeKind;
+use crate::linter::{LintIssue, LintSeverity};
+use tree_sitter::Node;
+
+/// This rule checks for local variables, constants, function arguments, and
+/// loop variables with the same name as a member of the class, which they
+/// hide in the rest of the function. It's the equivalent of Godot's
+/// SHADOWED_VARIABLE warning.
+pub struct ShadowedVariableRule;
+
+impl Rule for ShadowedVariableRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["source"]
+    }
+
+    fn check_node(&mut self, _node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let semantic = context.semantic;
+        let mut issues = Vec::new();
+
+        for (symbol_id, symbol) in semantic.symbols().iter().enumerate() {
+            let Some(description) = semantic.describe_local(symbol_id) else {
+                continue;
+            };
+            let Some(shadowed) = symbol.shadows.map(|shadowed| semantic.symbol(shadowed)) else {
+                continue;
+            };
+            if semantic.scope(shadowed.scope).kind != ScopeKind::Class {
+                continue;
+            }
+            issues.push(LintIssue::new(
+                symbol.line,
+                symbol.column,
+                "shadowed-variable".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "{} '{}' shadows the class member declared on line {}",
+                    description, symbol.name, shadowed.line
+                ),
+            ));
+        }
+
+        issues
+    }
+}
@@ -0,0 +1,55 @@
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity};
+use tree_sitter::Node;
+
+/// Scripts without an extends statement extend RefCounted.
+const DEFAULT_BASE_CLASS: &str = "RefCounted";
+
+/// This rule checks for local variables, constants, function arguments, and
+/// loop variables with the same name as a property, method, or signal of the
+/// built-in class the script extends, like `position` in a Node2D script.
+/// It's the equivalent of Godot's SHADOWED_VARIABLE_BASE_CLASS warning.
+///
+/// The linter only knows the classes of Godot's API, so it doesn't check
+/// scripts that extend other scripts.
+pub struct ShadowedVariableBaseClassRule;
+
+impl Rule for ShadowedVariableBaseClassRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["source"]
+    }
+
+    fn check_node(&mut self, _node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let semantic = context.semantic;
+        let mut issues = Vec::new();
+
+        for (symbol_id, symbol) in semantic.symbols().iter().enumerate() {
+            let Some(description) = semantic.describe_local(symbol_id) else {
+                continue;
+            };
+            let base_class = semantic
+                .base_class(symbol.scope)
+                .unwrap_or(DEFAULT_BASE_CLASS);
+            let Some((class_name, member_kind)) =
+                context.godot_api.find_member(base_class, &symbol.name)
+            else {
+                continue;
+            };
+            issues.push(LintIssue::new(
+                symbol.line,
+                symbol.column,
+                "shadowed-variable-base-class".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "{} '{}' shadows a {} of the base class '{}'",
+                    description,
+                    symbol.name,
+                    member_kind.as_str(),
+                    class_name
+                ),
+            ));
+        }
+
+        issues
+    }
+}
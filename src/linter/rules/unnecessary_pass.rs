@@ -1,5 +1,5 @@
 use crate::linter::lib::get_line_column;
-use crate::linter::rules::Rule;
+use crate::linter::rules::{LintContext, Rule};
 use crate::linter::{LintIssue, LintSeverity};
 use tree_sitter::Node;
 pub struct UnnecessaryPassRule;
@@ -9,7 +9,7 @@ impl Rule for UnnecessaryPassRule {
         &["body", "class_body"]
     }
 
-    fn check_node(&mut self, node: &Node, _source_code: &str) -> Vec<LintIssue> {
+    fn check_node(&mut self, node: &Node, _context: &LintContext) -> Vec<LintIssue> {
         let mut issues = Vec::new();
         let mut has_other_statements = false;
         let mut pass_nodes = Vec::new();
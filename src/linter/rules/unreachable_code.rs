@@ -0,0 +1,35 @@
+use crate::linter::control_flow::ControlFlowGraph;
+use crate::linter::lib::get_line_column;
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity};
+use tree_sitter::Node;
+
+/// This rule checks for statements that can never run, like statements after a
+/// return, break, or continue statement, or after an infinite `while true:`
+/// loop without a break. We report the first statement of each unreachable
+/// sequence of statements. It's off by default.
+pub struct UnreachableCodeRule;
+
+impl Rule for UnreachableCodeRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["function_definition", "constructor_definition", "lambda"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let graph = ControlFlowGraph::build(node, context.source_code);
+        graph
+            .unreachable_statements()
+            .into_iter()
+            .map(|statement| {
+                let (line, column) = get_line_column(&statement);
+                LintIssue::new(
+                    line,
+                    column,
+                    "unreachable-code".to_string(),
+                    LintSeverity::Warning,
+                    "Unreachable code. The code before it always returns, breaks, continues, or loops forever".to_string(),
+                )
+            })
+            .collect()
+    }
+}
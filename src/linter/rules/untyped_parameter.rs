@@ -0,0 +1,71 @@
+use crate::linter::lib::{DeclaredType, get_declared_type, get_line_column, get_node_text};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use tree_sitter::Node;
+
+/// This rule checks for function arguments without a static type, like Godot's
+/// UNTYPED_DECLARATION warning. It's off by default. Arguments that infer their
+/// type from their default value with `:=` count as typed unless the config
+/// disallows inferred types.
+pub struct UntypedParameterRule {
+    allow_inferred_types: bool,
+}
+
+impl UntypedParameterRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            allow_inferred_types: config.allow_inferred_types,
+        }
+    }
+}
+
+impl Rule for UntypedParameterRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["parameters"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        // Lambdas are often short callbacks, so we only check functions
+        if node.parent().is_none_or(|parent| parent.kind() == "lambda") {
+            return Vec::new();
+        }
+
+        let mut issues = Vec::new();
+        let mut cursor = node.walk();
+        for parameter in node.named_children(&mut cursor) {
+            let name_node = if parameter.kind() == "identifier" {
+                parameter
+            } else if let Some(name_node) = parameter.named_child(0) {
+                name_node
+            } else {
+                continue;
+            };
+            if name_node.kind() != "identifier" {
+                continue;
+            }
+
+            let name = get_node_text(&name_node, context.source_code);
+            let (line, column) = get_line_column(&name_node);
+            let message = match get_declared_type(&parameter) {
+                DeclaredType::Explicit => continue,
+                DeclaredType::Inferred if self.allow_inferred_types => continue,
+                DeclaredType::Inferred => format!(
+                    "Function argument '{}' infers its type with ':='. Declare its type explicitly",
+                    name
+                ),
+                DeclaredType::Missing => {
+                    format!("Function argument '{}' has no static type", name)
+                }
+            };
+            issues.push(LintIssue::new(
+                line,
+                column,
+                "untyped-parameter".to_string(),
+                LintSeverity::Warning,
+                message,
+            ));
+        }
+
+        issues
+    }
+}
@@ -0,0 +1,51 @@
+use crate::linter::lib::{DeclaredType, get_declared_type, get_line_column, get_node_text};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use tree_sitter::Node;
+
+/// This rule checks for member and local variables without a static type, like
+/// Godot's UNTYPED_DECLARATION warning. It's off by default. Variables that
+/// infer their type with `:=` count as typed unless the config disallows
+/// inferred types.
+pub struct UntypedVariableRule {
+    allow_inferred_types: bool,
+}
+
+impl UntypedVariableRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            allow_inferred_types: config.allow_inferred_types,
+        }
+    }
+}
+
+impl Rule for UntypedVariableRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["variable_statement"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let Some(name_node) = node.child_by_field_name("name") else {
+            return Vec::new();
+        };
+        let name = get_node_text(&name_node, context.source_code);
+        let message = match get_declared_type(node) {
+            DeclaredType::Explicit => return Vec::new(),
+            DeclaredType::Inferred if self.allow_inferred_types => return Vec::new(),
+            DeclaredType::Inferred => format!(
+                "Variable '{}' infers its type with ':='. Declare its type explicitly",
+                name
+            ),
+            DeclaredType::Missing => format!("Variable '{}' has no static type", name),
+        };
+
+        let (line, column) = get_line_column(&name_node);
+        vec![LintIssue::new(
+            line,
+            column,
+            "untyped-variable".to_string(),
+            LintSeverity::Warning,
+            message,
+        )]
+    }
+}
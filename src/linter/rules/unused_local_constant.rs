@@ -0,0 +1,60 @@
+use crate::linter::lib::{has_side_effects, remove_statement_fix};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::semantic::ScopeKind;
+use crate::linter::{LintIssue, LintSeverity};
+use tree_sitter::Node;
+
+/// This rule checks for constants declared in a function that the code never
+/// uses, and offers a fix that removes their declaration. Constants that start
+/// with an underscore are ignored by this rule.
+pub struct UnusedLocalConstantRule;
+
+impl Rule for UnusedLocalConstantRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["const_statement"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
+        let semantic = context.semantic;
+        let mut issues = Vec::new();
+
+        let Some(symbol_id) = node
+            .child_by_field_name("name")
+            .and_then(|name| semantic.declared_symbol(&name))
+        else {
+            return issues;
+        };
+        let symbol = semantic.symbol(symbol_id);
+        if semantic.scope(symbol.scope).kind == ScopeKind::Class
+            || symbol.name.starts_with('_')
+            || semantic.reads(symbol_id).next().is_some()
+        {
+            return issues;
+        }
+
+        // Constant values can still call functions like preload()
+        let fix = if node
+            .child_by_field_name("value")
+            .is_some_and(|value| has_side_effects(&value))
+        {
+            None
+        } else {
+            remove_statement_fix(node, source_code)
+        };
+        issues.push(
+            LintIssue::new(
+                symbol.line,
+                symbol.column,
+                "unused-local-constant".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "Local constant '{}' is unused. Consider removing it",
+                    symbol.name
+                ),
+            )
+            .with_fix(fix),
+        );
+        issues
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::GodotVersion;
+use crate::godot_api::GodotApi;
+use crate::linter::lib::get_node_text;
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::semantic::{ScopeKind, SymbolId, SymbolKind};
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use crate::reorder::builtin_virtual_methods;
+use tree_sitter::Node;
+
+/// This rule checks for pseudo-private class members, the ones that start
+/// with an underscore, that the script never uses. Like the private-access
+/// rule, we consider that only the class itself uses them.
+///
+/// A member counts as used if code in the class refers to it through the
+/// semantic model, like `_value`, or through `self`, like `self._value`. The
+/// names of property setters and getters and strings that name a member of
+/// the class, like in `Callable(self, "_on_timeout")`, also count. A local
+/// variable with the same name or `other._value` doesn't. Godot calls virtual
+/// methods like `_ready()`, including the ones of the built-in class the
+/// script extends, so these are never reported.
+///
+/// Uses from inside the member's own declaration, like a method calling
+/// itself, and assignments like `_value = 1` or `self._value = 1` don't count:
+/// the member would still be unused if you removed them.
+///
+/// It's off by default: methods connected to signals in scenes, like
+/// `_on_button_pressed()`, look unused to the linter.
+pub struct UnusedPrivateMemberRule {
+    godot_version: GodotVersion,
+}
+
+impl UnusedPrivateMemberRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            godot_version: config.godot_version,
+        }
+    }
+
+    /// Returns true for virtual methods, either of all objects or of the
+    /// built-in class the method's class extends, like `_draw()` for nodes
+    /// that extend CanvasItem.
+    fn is_called_by_godot(&self, method_name: &str, base_class: &str, api: &GodotApi) -> bool {
+        method_name == "_static_init"
+            || builtin_virtual_methods(self.godot_version).contains(&method_name)
+            || api.is_virtual_method(base_class, method_name)
+    }
+}
+
+impl Rule for UnusedPrivateMemberRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["source"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let semantic = context.semantic;
+        let mut issues = Vec::new();
+
+        let mut member_uses = Vec::new();
+        let mut declarations = HashMap::new();
+        collect_member_uses(node, context, &mut member_uses, &mut declarations);
+
+        for (id, symbol) in semantic.symbols().iter().enumerate() {
+            if semantic.scope(symbol.scope).kind != ScopeKind::Class
+                || !symbol.name.starts_with('_')
+            {
+                continue;
+            }
+            let declaration = declarations.get(&id).cloned().unwrap_or_default();
+            let is_used = semantic
+                .reads(id)
+                .map(|reference| reference.start_byte)
+                .chain(
+                    member_uses
+                        .iter()
+                        .filter(|(member, _)| *member == id)
+                        .map(|(_, start_byte)| *start_byte),
+                )
+                .any(|start_byte| !declaration.contains(&start_byte));
+            if is_used {
+                continue;
+            }
+            let kind_name = match symbol.kind {
+                SymbolKind::Variable => "variable",
+                SymbolKind::Constant => "constant",
+                SymbolKind::Signal => "signal",
+                SymbolKind::Class => "class",
+                SymbolKind::Function => {
+                    let base_class = semantic.base_class(symbol.scope).unwrap_or("RefCounted");
+                    if self.is_called_by_godot(&symbol.name, base_class, context.godot_api) {
+                        continue;
+                    }
+                    "method"
+                }
+                _ => continue,
+            };
+            issues.push(LintIssue::new(
+                symbol.line,
+                symbol.column,
+                "unused-private-member".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "Private {} '{}' is never used. Consider removing it",
+                    kind_name, symbol.name
+                ),
+            ));
+        }
+
+        issues
+    }
+}
+
+/// Collects the class members that the code uses without a plain identifier,
+/// with the position of each use: through `self`, as a property setter or
+/// getter, or by name in a string. Also collects the byte range of each
+/// declaration so we can ignore the uses inside of it.
+fn collect_member_uses(
+    node: &Node,
+    context: &LintContext,
+    member_uses: &mut Vec<(SymbolId, usize)>,
+    declarations: &mut HashMap<SymbolId, Range<usize>>,
+) {
+    let source_code = context.source_code;
+    if let Some(name) = node.child_by_field_name("name")
+        && let Some(symbol) = context.semantic.declared_symbol(&name)
+    {
+        declarations.insert(symbol, node.byte_range());
+    }
+    let member_name = match node.kind() {
+        "setter" | "getter" => Some(get_node_text(node, source_code)),
+        "string" => Some(get_node_text(node, source_code).trim_matches(|c| c == '"' || c == '\'')),
+        "attribute" => {
+            let mut cursor = node.walk();
+            let children: Vec<Node> = node.children(&mut cursor).collect();
+            match children.as_slice() {
+                [object, dot, member, ..]
+                    if dot.kind() == "."
+                        && get_node_text(object, source_code) == "self"
+                        && !(children.len() == 3 && is_assignment_target(node)) =>
+                {
+                    let name_node = match member.kind() {
+                        "attribute_call" => member.child(0),
+                        _ => Some(*member),
+                    };
+                    name_node.map(|name_node| get_node_text(&name_node, source_code))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    if let Some(name) = member_name
+        && let Some(member) = find_class_member(node, name, context)
+    {
+        member_uses.push((member, node.start_byte()));
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_member_uses(&child, context, member_uses, declarations);
+    }
+}
+
+/// Returns true if the node is the left side of an assignment like `x = 1`.
+fn is_assignment_target(node: &Node) -> bool {
+    node.parent().is_some_and(|parent| {
+        parent.kind() == "assignment"
+            && parent
+                .child_by_field_name("left")
+                .is_some_and(|left| left.id() == node.id())
+    })
+}
+
+/// Finds the member with the given name of the class containing the node.
+fn find_class_member(node: &Node, name: &str, context: &LintContext) -> Option<SymbolId> {
+    let semantic = context.semantic;
+    let mut current = Some(semantic.enclosing_scope(node));
+    while let Some(id) = current {
+        let scope = semantic.scope(id);
+        if scope.kind == ScopeKind::Class {
+            return scope
+                .symbols
+                .iter()
+                .copied()
+                .find(|&symbol| semantic.symbol(symbol).name == name);
+        }
+        current = scope.parent;
+    }
+    None
+}
@@ -0,0 +1,104 @@
+use crate::linter::lib::{has_side_effects, remove_statement_fix};
+use crate::linter::rules::{LintContext, Rule};
+use crate::linter::semantic::{ReferenceKind, ScopeKind};
+use crate::linter::{LintIssue, LintSeverity};
+use tree_sitter::Node;
+
+/// This rule checks for local variables and loop variables the code never
+/// reads. Variables that start with an underscore are ignored by this rule.
+///
+/// Declarations of variables the code never uses at all get a fix that removes
+/// them, unless computing their value may have side effects, like a function
+/// call. Variables the code assigns but never reads have no fix, as we'd have
+/// to remove the assignments too.
+pub struct UnusedVariableRule;
+
+impl Rule for UnusedVariableRule {
+    fn get_target_ast_nodes(&self) -> &[&str] {
+        &["variable_statement", "for_statement"]
+    }
+
+    fn check_node(&mut self, node: &Node, context: &LintContext) -> Vec<LintIssue> {
+        let source_code = context.source_code;
+        let semantic = context.semantic;
+        let mut issues = Vec::new();
+
+        let name_node = if node.kind() == "for_statement" {
+            node.child_by_field_name("left").and_then(|left| {
+                if left.kind() == "identifier" {
+                    Some(left)
+                } else {
+                    left.named_child(0)
+                }
+            })
+        } else {
+            node.child_by_field_name("name")
+        };
+        let Some(symbol_id) = name_node.and_then(|name| semantic.declared_symbol(&name)) else {
+            return issues;
+        };
+        let symbol = semantic.symbol(symbol_id);
+        // Class variables are part of the class's interface
+        if semantic.scope(symbol.scope).kind == ScopeKind::Class
+            || symbol.name.starts_with('_')
+            || semantic.reads(symbol_id).next().is_some()
+        {
+            return issues;
+        }
+
+        if node.kind() == "for_statement" {
+            issues.push(LintIssue::new(
+                symbol.line,
+                symbol.column,
+                "unused-variable".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "Loop variable '{}' is unused. Consider prefixing it with '_'",
+                    symbol.name
+                ),
+            ));
+            return issues;
+        }
+
+        let is_assigned = symbol
+            .references
+            .iter()
+            .any(|&reference| semantic.reference(reference).kind == ReferenceKind::Write);
+        if is_assigned {
+            issues.push(LintIssue::new(
+                symbol.line,
+                symbol.column,
+                "unused-variable".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "Local variable '{}' is assigned but never used. Consider removing it or prefixing with '_'",
+                    symbol.name
+                ),
+            ));
+            return issues;
+        }
+
+        let fix = if node
+            .child_by_field_name("value")
+            .is_some_and(|value| has_side_effects(&value))
+        {
+            None
+        } else {
+            remove_statement_fix(node, source_code)
+        };
+        issues.push(
+            LintIssue::new(
+                symbol.line,
+                symbol.column,
+                "unused-variable".to_string(),
+                LintSeverity::Warning,
+                format!(
+                    "Local variable '{}' is unused. Consider removing it or prefixing with '_'",
+                    symbol.name
+                ),
+            )
+            .with_fix(fix),
+        );
+        issues
+    }
+}
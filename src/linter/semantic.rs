@@ -0,0 +1,706 @@
+//! This module builds a semantic model of a GDScript file for lint rules: the
+//! scopes of the file, the symbols declared in each scope, and what each
+//! identifier in the code refers to.
+//!
+//! We build the model once per file, before running the rules. Rules get it
+//! through the `LintContext` passed to `Rule::check_node()` and
+//! `Rule::finalize()`, so they can tell apart a function argument and a lambda
+//! parameter with the same name, or a variable and a dictionary key.
+//!
+//! The model doesn't know about Godot's built-in classes and global functions:
+//! references to them don't resolve to any symbol.
+use std::collections::HashMap;
+
+use tree_sitter::Node;
+
+use crate::linter::lib::{get_line_column, get_node_text};
+
+pub type ScopeId = usize;
+pub type SymbolId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    /// The file itself, or an inner class
+    Class,
+    /// A function, a constructor, or a property's set or get function
+    Function,
+    /// The body of a block statement, like an if or a for loop
+    Block,
+    Lambda,
+    /// A branch of a match statement, where pattern bindings like `var x` live
+    MatchPattern,
+}
+
+#[derive(Debug, Clone)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    pub parent: Option<ScopeId>,
+    /// Byte range of the node that creates the scope
+    pub start_byte: usize,
+    pub end_byte: usize,
+    /// The symbols declared directly in this scope, in the order of the code
+    pub symbols: Vec<SymbolId>,
+    /// For class scopes, the class in the extends statement, unless the class
+    /// extends a script path
+    pub base_class: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Class,
+    Function,
+    Signal,
+    Enum,
+    /// A member of an unnamed enum, which is a constant of the class
+    EnumMember,
+    Constant,
+    Variable,
+    Parameter,
+    LoopVariable,
+    PatternBinding,
+}
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub scope: ScopeId,
+    /// Position of the name in the declaration
+    pub line: usize,
+    pub column: usize,
+    pub start_byte: usize,
+    /// The symbol of an enclosing scope with the same name, which this one
+    /// hides
+    pub shadows: Option<SymbolId>,
+    pub references: Vec<ReferenceId>,
+}
+
+pub type ReferenceId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    Read,
+    /// The left side of an assignment like `x = 1`. Augmented assignments
+    /// like `x += 1` read the variable, so they count as reads.
+    Write,
+}
+
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub name: String,
+    pub kind: ReferenceKind,
+    /// The innermost scope the identifier appears in
+    pub scope: ScopeId,
+    /// The symbol the identifier refers to, if it's declared in the file
+    pub symbol: Option<SymbolId>,
+    /// True if a lambda uses a local variable of an enclosing function
+    pub is_capture: bool,
+    pub line: usize,
+    pub column: usize,
+    pub start_byte: usize,
+}
+
+/// The scopes, symbols, and references of a file.
+#[derive(Debug, Clone, Default)]
+pub struct SemanticModel {
+    scopes: Vec<Scope>,
+    symbols: Vec<Symbol>,
+    references: Vec<Reference>,
+    /// Maps the id of nodes that create a scope to the scope
+    scope_by_node: HashMap<usize, ScopeId>,
+    /// Maps the start byte of declared names to their symbol
+    symbol_by_name_node: HashMap<usize, SymbolId>,
+    /// Maps the start byte of identifiers to their reference
+    reference_by_node: HashMap<usize, ReferenceId>,
+}
+
+impl SemanticModel {
+    /// Builds the semantic model of a file from the root node of its tree.
+    pub fn build(root: &Node, source_code: &str) -> Self {
+        let mut builder = Builder {
+            model: SemanticModel::default(),
+            source_code,
+        };
+        let scope = builder.push_scope(ScopeKind::Class, None, root);
+        builder.model.scopes[scope].base_class = builder.find_base_class(root);
+        builder.visit_class_body(root, scope);
+        builder.model
+    }
+
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+
+    pub fn scope(&self, id: ScopeId) -> &Scope {
+        &self.scopes[id]
+    }
+
+    pub fn symbol(&self, id: SymbolId) -> &Symbol {
+        &self.symbols[id]
+    }
+
+    pub fn reference(&self, id: ReferenceId) -> &Reference {
+        &self.references[id]
+    }
+
+    /// Returns the scope a node creates, like the function scope of a
+    /// function definition or the block scope of an if statement's body.
+    pub fn scope_of_node(&self, node: &Node) -> Option<ScopeId> {
+        self.scope_by_node.get(&node.id()).copied()
+    }
+
+    /// Returns the innermost scope containing the node.
+    pub fn enclosing_scope(&self, node: &Node) -> ScopeId {
+        // We create scopes in the order of the code, and nested scopes after
+        // the scopes around them, so the last match is the innermost one
+        self.scopes
+            .iter()
+            .rposition(|scope| {
+                scope.start_byte <= node.start_byte() && node.end_byte() <= scope.end_byte
+            })
+            .unwrap_or(0)
+    }
+
+    /// Describes a symbol declared in a function or a lambda for lint
+    /// messages, like "Local variable". Returns None for class members.
+    pub fn describe_local(&self, symbol: SymbolId) -> Option<&'static str> {
+        let symbol = &self.symbols[symbol];
+        if self.scopes[symbol.scope].kind == ScopeKind::Class {
+            return None;
+        }
+        match symbol.kind {
+            SymbolKind::Variable => Some("Local variable"),
+            SymbolKind::Constant => Some("Local constant"),
+            SymbolKind::Parameter => Some("Function argument"),
+            SymbolKind::LoopVariable => Some("Loop variable"),
+            SymbolKind::PatternBinding => Some("Pattern binding"),
+            _ => None,
+        }
+    }
+
+    /// Returns the class that the class containing the scope extends.
+    pub fn base_class(&self, scope: ScopeId) -> Option<&str> {
+        let mut current = Some(scope);
+        while let Some(id) = current {
+            let scope = &self.scopes[id];
+            if scope.kind == ScopeKind::Class {
+                return scope.base_class.as_deref();
+            }
+            current = scope.parent;
+        }
+        None
+    }
+
+    /// Returns the symbol a declaration's name node declares.
+    pub fn declared_symbol(&self, name_node: &Node) -> Option<SymbolId> {
+        self.symbol_by_name_node
+            .get(&name_node.start_byte())
+            .copied()
+    }
+
+    /// Returns the symbol an identifier refers to, if it's declared in the file.
+    pub fn resolve(&self, identifier: &Node) -> Option<SymbolId> {
+        self.reference_by_node
+            .get(&identifier.start_byte())
+            .and_then(|&reference| self.references[reference].symbol)
+    }
+
+    /// Returns the places where the code reads the symbol's value.
+    pub fn reads(&self, symbol: SymbolId) -> impl Iterator<Item = &Reference> {
+        self.symbols[symbol]
+            .references
+            .iter()
+            .map(|&reference| &self.references[reference])
+            .filter(|reference| reference.kind == ReferenceKind::Read)
+    }
+
+    /// Returns the symbols of enclosing functions a lambda uses.
+    pub fn captures(&self, lambda_scope: ScopeId) -> Vec<SymbolId> {
+        let mut captures = Vec::new();
+        for reference in &self.references {
+            if reference.is_capture
+                && self.is_within(reference.scope, lambda_scope)
+                && let Some(symbol) = reference.symbol
+                && !self.is_within(self.symbols[symbol].scope, lambda_scope)
+                && !captures.contains(&symbol)
+            {
+                captures.push(symbol);
+            }
+        }
+        captures
+    }
+
+    /// Returns true if `scope` is `ancestor` or one of its descendants.
+    pub fn is_within(&self, scope: ScopeId, ancestor: ScopeId) -> bool {
+        let mut current = Some(scope);
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.scopes[id].parent;
+        }
+        false
+    }
+
+    /// Finds the symbol a name refers to from a scope. Code in a class sees
+    /// the members of the class, but only the constants, enums, and classes of
+    /// the classes around it.
+    fn lookup(&self, name: &str, scope: ScopeId) -> Option<SymbolId> {
+        let mut passed_class = false;
+        let mut current = Some(scope);
+        while let Some(id) = current {
+            let scope = &self.scopes[id];
+            let found = scope.symbols.iter().rev().copied().find(|&symbol| {
+                let symbol = &self.symbols[symbol];
+                symbol.name == name
+                    && (!passed_class
+                        || matches!(
+                            symbol.kind,
+                            SymbolKind::Class
+                                | SymbolKind::Enum
+                                | SymbolKind::EnumMember
+                                | SymbolKind::Constant
+                        ))
+            });
+            if found.is_some() {
+                return found;
+            }
+            if scope.kind == ScopeKind::Class {
+                passed_class = true;
+            }
+            current = scope.parent;
+        }
+        None
+    }
+}
+
+struct Builder<'a> {
+    model: SemanticModel,
+    source_code: &'a str,
+}
+
+impl Builder<'_> {
+    fn push_scope(&mut self, kind: ScopeKind, parent: Option<ScopeId>, node: &Node) -> ScopeId {
+        let id = self.model.scopes.len();
+        self.model.scopes.push(Scope {
+            kind,
+            parent,
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            symbols: Vec::new(),
+            base_class: None,
+        });
+        self.model.scope_by_node.insert(node.id(), id);
+        id
+    }
+
+    fn declare(&mut self, name_node: &Node, kind: SymbolKind, scope: ScopeId) {
+        let name = get_node_text(name_node, self.source_code).to_string();
+        let shadows = self.model.scopes[scope]
+            .parent
+            .and_then(|parent| self.model.lookup(&name, parent));
+        let (line, column) = get_line_column(name_node);
+        let id = self.model.symbols.len();
+        self.model.symbols.push(Symbol {
+            name,
+            kind,
+            scope,
+            line,
+            column,
+            start_byte: name_node.start_byte(),
+            shadows,
+            references: Vec::new(),
+        });
+        self.model.scopes[scope].symbols.push(id);
+        self.model
+            .symbol_by_name_node
+            .insert(name_node.start_byte(), id);
+    }
+
+    fn add_reference(&mut self, identifier: &Node, kind: ReferenceKind, scope: ScopeId) {
+        let name = get_node_text(identifier, self.source_code).to_string();
+        let symbol = self.model.lookup(&name, scope);
+        // A lambda captures a symbol if it comes from a function around it
+        let is_capture = symbol.is_some_and(|symbol| {
+            let symbol_scope = self.model.symbols[symbol].scope;
+            self.model.scopes[symbol_scope].kind != ScopeKind::Class
+                && self.crosses_lambda(scope, symbol_scope)
+        });
+        let (line, column) = get_line_column(identifier);
+        let id = self.model.references.len();
+        self.model.references.push(Reference {
+            name,
+            kind,
+            scope,
+            symbol,
+            is_capture,
+            line,
+            column,
+            start_byte: identifier.start_byte(),
+        });
+        if let Some(symbol) = symbol {
+            self.model.symbols[symbol].references.push(id);
+        }
+        self.model
+            .reference_by_node
+            .insert(identifier.start_byte(), id);
+    }
+
+    /// Returns true if there's a lambda scope between `scope` and its ancestor
+    /// `ancestor`.
+    fn crosses_lambda(&self, scope: ScopeId, ancestor: ScopeId) -> bool {
+        let mut current = Some(scope);
+        while let Some(id) = current
+            && id != ancestor
+        {
+            if self.model.scopes[id].kind == ScopeKind::Lambda {
+                return true;
+            }
+            current = self.model.scopes[id].parent;
+        }
+        false
+    }
+
+    /// Declares the members of a class first, as methods can use members
+    /// declared below them, then visits the class's code.
+    fn visit_class_body(&mut self, body: &Node, scope: ScopeId) {
+        let mut cursor = body.walk();
+        let members: Vec<Node> = body.named_children(&mut cursor).collect();
+        for member in &members {
+            let kind = match member.kind() {
+                "variable_statement"
+                | "export_variable_statement"
+                | "onready_variable_statement" => SymbolKind::Variable,
+                "const_statement" => SymbolKind::Constant,
+                "signal_statement" => SymbolKind::Signal,
+                "function_definition" | "constructor_definition" => SymbolKind::Function,
+                "class_definition" => SymbolKind::Class,
+                "enum_definition" => {
+                    self.declare_enum(member, scope);
+                    continue;
+                }
+                _ => continue,
+            };
+            if let Some(name) = member.child_by_field_name("name") {
+                self.declare(&name, kind, scope);
+            }
+        }
+
+        for member in &members {
+            match member.kind() {
+                "class_definition" => {
+                    let mut class_cursor = member.walk();
+                    let class_body = member
+                        .named_children(&mut class_cursor)
+                        .find(|child| child.kind() == "class_body");
+                    if let Some(class_body) = class_body {
+                        let class_scope = self.push_scope(ScopeKind::Class, Some(scope), member);
+                        self.model.scopes[class_scope].base_class = self.find_base_class(member);
+                        self.visit_class_body(&class_body, class_scope);
+                    }
+                }
+                // `visit()` doesn't declare class members again
+                _ => self.visit(member, scope),
+            }
+        }
+    }
+
+    /// Finds the extends statement of the file or of an inner class, which
+    /// can also be part of a class_name statement or of the class's body.
+    fn find_base_class(&self, class_node: &Node) -> Option<String> {
+        let mut cursor = class_node.walk();
+        for child in class_node.named_children(&mut cursor) {
+            let base_class = match child.kind() {
+                "extends_statement" => {
+                    let text = get_node_text(&child, self.source_code);
+                    let class_name = text.trim_start_matches("extends").trim();
+                    // Scripts can extend other scripts by path
+                    if class_name.starts_with(['"', '\'']) {
+                        return None;
+                    }
+                    Some(class_name.to_string())
+                }
+                "class_name_statement" | "class_body" => self.find_base_class(&child),
+                _ => None,
+            };
+            if base_class.is_some() {
+                return base_class;
+            }
+        }
+        None
+    }
+
+    fn declare_enum(&mut self, node: &Node, scope: ScopeId) {
+        if let Some(name) = node.child_by_field_name("name") {
+            self.declare(&name, SymbolKind::Enum, scope);
+            return;
+        }
+        // The members of unnamed enums are constants of the class
+        let mut cursor = node.walk();
+        for list in node.named_children(&mut cursor) {
+            if list.kind() != "enumerator_list" {
+                continue;
+            }
+            let mut list_cursor = list.walk();
+            for enumerator in list.named_children(&mut list_cursor) {
+                if enumerator.kind() == "enumerator"
+                    && let Some(name) = enumerator.named_child(0)
+                {
+                    self.declare(&name, SymbolKind::EnumMember, scope);
+                }
+            }
+        }
+    }
+
+    fn visit(&mut self, node: &Node, scope: ScopeId) {
+        match node.kind() {
+            "identifier" => {
+                if self.is_reference(node) {
+                    self.add_reference(node, ReferenceKind::Read, scope);
+                }
+            }
+            // Names of declarations, types, and annotations aren't references
+            "name" | "type" | "comment" | "string" | "get_node" | "node_path" => {}
+            "annotation" => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    if child.kind() == "arguments" {
+                        self.visit(&child, scope);
+                    }
+                }
+            }
+            "signal_statement" => {}
+            "enum_definition" => {
+                if self.model.scopes[scope].kind != ScopeKind::Class {
+                    self.declare_enum(node, scope);
+                }
+                self.visit_enumerator_values(node, scope);
+            }
+            "function_definition" | "constructor_definition" => {
+                let function_scope = self.push_scope(ScopeKind::Function, Some(scope), node);
+                self.visit_function(node, function_scope);
+            }
+            "lambda" => {
+                let lambda_scope = self.push_scope(ScopeKind::Lambda, Some(scope), node);
+                // A named lambda can call itself
+                if let Some(name) = node.child_by_field_name("name") {
+                    self.declare(&name, SymbolKind::Function, lambda_scope);
+                }
+                self.visit_function(node, lambda_scope);
+            }
+            // Property setters have a parameter for the new value
+            "set_body" | "get_body" => {
+                let function_scope = self.push_scope(ScopeKind::Function, Some(scope), node);
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    match child.kind() {
+                        "identifier" => self.declare(&child, SymbolKind::Parameter, function_scope),
+                        "body" => self.visit_children(&child, function_scope),
+                        _ => self.visit(&child, function_scope),
+                    }
+                }
+            }
+            "variable_statement" | "const_statement" => {
+                self.visit_children(node, scope);
+                // Class members are declared before we visit the class's code
+                if self.model.scopes[scope].kind != ScopeKind::Class
+                    && let Some(name) = node.child_by_field_name("name")
+                {
+                    let kind = if node.kind() == "const_statement" {
+                        SymbolKind::Constant
+                    } else {
+                        SymbolKind::Variable
+                    };
+                    self.declare(&name, kind, scope);
+                }
+            }
+            "for_statement" => self.visit_for_statement(node, scope),
+            "match_statement" => self.visit_match_statement(node, scope),
+            "body" => {
+                let block_scope = self.push_scope(ScopeKind::Block, Some(scope), node);
+                self.visit_children(node, block_scope);
+            }
+            "assignment" => {
+                let left = node.child_by_field_name("left");
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    if left.is_some_and(|left| left.id() == child.id())
+                        && child.kind() == "identifier"
+                    {
+                        self.add_reference(&child, ReferenceKind::Write, scope);
+                    } else {
+                        self.visit(&child, scope);
+                    }
+                }
+            }
+            _ => self.visit_children(node, scope),
+        }
+    }
+
+    fn visit_children(&mut self, node: &Node, scope: ScopeId) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.visit(&child, scope);
+        }
+    }
+
+    fn visit_enumerator_values(&mut self, node: &Node, scope: ScopeId) {
+        let mut cursor = node.walk();
+        for list in node.named_children(&mut cursor) {
+            let mut list_cursor = list.walk();
+            for enumerator in list.named_children(&mut list_cursor) {
+                if let Some(value) = enumerator.named_child(1) {
+                    self.visit(&value, scope);
+                }
+            }
+        }
+    }
+
+    /// Declares the parameters of a function or lambda in its scope and visits
+    /// its body in the same scope.
+    fn visit_function(&mut self, node: &Node, scope: ScopeId) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            match child.kind() {
+                "parameters" => {
+                    let mut parameters_cursor = child.walk();
+                    for parameter in child.named_children(&mut parameters_cursor) {
+                        self.visit_parameter(&parameter, scope);
+                    }
+                }
+                "body" => self.visit_children(&child, scope),
+                "name" | "type" => {}
+                _ => self.visit(&child, scope),
+            }
+        }
+    }
+
+    fn visit_parameter(&mut self, parameter: &Node, scope: ScopeId) {
+        let name = if parameter.kind() == "identifier" {
+            Some(*parameter)
+        } else {
+            parameter.named_child(0)
+        };
+        // Default values can use the parameters before them
+        let mut cursor = parameter.walk();
+        for child in parameter.named_children(&mut cursor).skip(1) {
+            self.visit(&child, scope);
+        }
+        if let Some(name) = name.filter(|name| name.kind() == "identifier") {
+            self.declare(&name, SymbolKind::Parameter, scope);
+        }
+    }
+
+    fn visit_for_statement(&mut self, node: &Node, scope: ScopeId) {
+        if let Some(right) = node.child_by_field_name("right") {
+            self.visit(&right, scope);
+        }
+        let Some(body) = node.child_by_field_name("body") else {
+            return;
+        };
+        let block_scope = self.push_scope(ScopeKind::Block, Some(scope), &body);
+        if let Some(left) = node.child_by_field_name("left") {
+            let name = if left.kind() == "identifier" {
+                Some(left)
+            } else {
+                left.named_child(0)
+            };
+            if let Some(name) = name {
+                self.declare(&name, SymbolKind::LoopVariable, block_scope);
+            }
+        }
+        self.visit_children(&body, block_scope);
+    }
+
+    fn visit_match_statement(&mut self, node: &Node, scope: ScopeId) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if child.kind() != "match_body" {
+                self.visit(&child, scope);
+                continue;
+            }
+            let mut body_cursor = child.walk();
+            for section in child.named_children(&mut body_cursor) {
+                if section.kind() != "pattern_section" {
+                    self.visit(&section, scope);
+                    continue;
+                }
+                let section_scope = self.push_scope(ScopeKind::MatchPattern, Some(scope), &section);
+                let mut section_cursor = section.walk();
+                let children: Vec<Node> = section.named_children(&mut section_cursor).collect();
+                // Bindings come first so the guard and the body can use them
+                for pattern in &children {
+                    if pattern.kind() != "body" && pattern.kind() != "pattern_guard" {
+                        self.visit_pattern(pattern, section_scope);
+                    }
+                }
+                for child in &children {
+                    match child.kind() {
+                        "body" => self.visit_children(child, section_scope),
+                        "pattern_guard" => self.visit(child, section_scope),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// Declares the bindings of a match pattern, like `var x`. Other
+    /// identifiers in patterns are constants the value gets compared to.
+    fn visit_pattern(&mut self, pattern: &Node, scope: ScopeId) {
+        if pattern.kind() == "pattern_binding" {
+            let mut cursor = pattern.walk();
+            if let Some(name) = pattern
+                .named_children(&mut cursor)
+                .find(|child| child.kind() == "identifier")
+            {
+                self.declare(&name, SymbolKind::PatternBinding, scope);
+            }
+            return;
+        }
+        if pattern.kind() == "identifier" {
+            self.visit(pattern, scope);
+            return;
+        }
+        let mut cursor = pattern.walk();
+        for child in pattern.named_children(&mut cursor) {
+            self.visit_pattern(&child, scope);
+        }
+    }
+
+    /// Returns false for identifiers that name something else than a symbol in
+    /// scope: declared names, attributes like `dict.key`, method names in
+    /// `obj.method()`, and keys of Lua-style dictionaries like `{key = value}`.
+    fn is_reference(&self, identifier: &Node) -> bool {
+        let Some(parent) = identifier.parent() else {
+            return true;
+        };
+        // The name of a declaration
+        if parent
+            .child_by_field_name("name")
+            .is_some_and(|name| name.id() == identifier.id())
+        {
+            return false;
+        }
+        match parent.kind() {
+            "attribute" => identifier
+                .prev_sibling()
+                .is_none_or(|previous| previous.kind() != "."),
+            "attribute_call" | "attribute_subscript" => identifier
+                .prev_sibling()
+                .is_some_and(|previous| previous.kind() != "."),
+            "pair" => identifier
+                .next_sibling()
+                .is_none_or(|next| next.kind() != "="),
+            _ => true,
+        }
+    }
+}
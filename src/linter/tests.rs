@@ -96,4 +96,517 @@ signal BadSignal
 
         assert!(crate::linter::rule_config::validate_rule_names(&invalid_rules).is_err());
     }
+
+    #[test]
+    fn test_lint_godot_4_syntax_in_godot_3_script() {
+        let test_code = r#"
+@tool
+extends Node
+
+var health = 100:
+    set = set_health
+
+
+func wait():
+    await get_tree().process_frame
+"#;
+
+        let config = LinterConfig {
+            godot_version: crate::GodotVersion::Godot3,
+            ..Default::default()
+        };
+        let issues = lint_gdscript_with_config(test_code, "test.gd", &config).unwrap();
+
+        let lines: Vec<usize> = issues
+            .iter()
+            .filter(|i| i.rule == "incompatible-syntax")
+            .map(|i| i.line)
+            .collect();
+        assert_eq!(lines, vec![2, 5, 10]);
+    }
+
+    /// Parses the code and builds its semantic model.
+    fn build_semantic_model(code: &str) -> crate::linter::semantic::SemanticModel {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_gdscript::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        crate::linter::semantic::SemanticModel::build(&tree.root_node(), code)
+    }
+
+    #[test]
+    fn test_semantic_model_resolves_references() {
+        use crate::linter::semantic::{ReferenceKind, ScopeKind, SymbolKind};
+
+        let test_code = r#"
+var speed = 10
+
+
+func move(delta, items):
+    var speed = delta * 2
+    for item in items:
+        print(item.speed)
+    var callback = func(delta): return delta + speed
+    var config = {speed = 1}
+    speed = callback.call(1)
+"#;
+
+        let semantic = build_semantic_model(test_code);
+        let find_symbols = |name: &str| -> Vec<usize> {
+            (0..semantic.symbols().len())
+                .filter(|&id| semantic.symbol(id).name == name)
+                .collect()
+        };
+
+        // The local variable hides the class member
+        let speeds = find_symbols("speed");
+        assert_eq!(speeds.len(), 2);
+        let (member, local) = (speeds[0], speeds[1]);
+        assert_eq!(semantic.symbol(local).shadows, Some(member));
+        assert_eq!(
+            semantic.scope(semantic.symbol(member).scope).kind,
+            ScopeKind::Class
+        );
+
+        // Neither the attribute nor the dictionary key use the variables, and
+        // the lambda captures the local variable
+        assert!(semantic.symbol(member).references.is_empty());
+        let local_references: Vec<_> = semantic
+            .symbol(local)
+            .references
+            .iter()
+            .map(|&id| semantic.reference(id))
+            .collect();
+        assert_eq!(local_references.len(), 2);
+        assert!(local_references[0].is_capture);
+        assert_eq!(local_references[1].kind, ReferenceKind::Write);
+
+        // The lambda parameter hides the function argument
+        let deltas = find_symbols("delta");
+        assert_eq!(deltas.len(), 2);
+        assert_eq!(semantic.symbol(deltas[0]).kind, SymbolKind::Parameter);
+        assert_eq!(semantic.reads(deltas[0]).count(), 1);
+        assert_eq!(semantic.reads(deltas[1]).count(), 1);
+        assert_eq!(semantic.symbol(deltas[1]).shadows, Some(deltas[0]));
+
+        let items = find_symbols("item");
+        assert_eq!(semantic.symbol(items[0]).kind, SymbolKind::LoopVariable);
+        assert_eq!(semantic.reads(items[0]).count(), 1);
+    }
+
+    #[test]
+    fn test_semantic_model_match_bindings() {
+        use crate::linter::semantic::{ScopeKind, SymbolKind};
+
+        let test_code = r#"
+const LIMIT = 3
+
+
+func check(value):
+    match value:
+        LIMIT:
+            pass
+        [var first, ..]:
+            print(first)
+"#;
+
+        let semantic = build_semantic_model(test_code);
+        let first = (0..semantic.symbols().len())
+            .find(|&id| semantic.symbol(id).name == "first")
+            .unwrap();
+        assert_eq!(semantic.symbol(first).kind, SymbolKind::PatternBinding);
+        assert_eq!(
+            semantic.scope(semantic.symbol(first).scope).kind,
+            ScopeKind::MatchPattern
+        );
+        assert_eq!(semantic.reads(first).count(), 1);
+
+        let limit = (0..semantic.symbols().len())
+            .find(|&id| semantic.symbol(id).name == "LIMIT")
+            .unwrap();
+        assert_eq!(semantic.reads(limit).count(), 1);
+    }
+
+    #[test]
+    fn test_unused_variable_fixes() {
+        use crate::linter::apply_fixes;
+
+        let test_code = "func run():
+	var unused = 1
+	var result = compute()
+	const UNUSED = 2
+	var used = 3
+	print(used)
+";
+
+        let config = LinterConfig::default();
+        let issues = lint_gdscript_with_config(test_code, "test.gd", &config).unwrap();
+        // We don't remove the call to compute(), as it may have side effects
+        let fixable: Vec<&str> = issues
+            .iter()
+            .filter(|issue| issue.fix.is_some())
+            .map(|issue| issue.rule.as_str())
+            .collect();
+        assert_eq!(fixable, vec!["unused-variable", "unused-local-constant"]);
+
+        let (fixed_code, applied) = apply_fixes(test_code, &issues);
+        assert_eq!(applied, 2);
+        assert_eq!(
+            fixed_code,
+            "func run():\n\tvar result = compute()\n\tvar used = 3\n\tprint(used)\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_fixes_skips_overlapping_fixes() {
+        use crate::linter::{LintFix, apply_fixes};
+
+        let issue = |start_byte, end_byte, replacement: &str| {
+            LintIssue::new(
+                1,
+                1,
+                "test".to_string(),
+                LintSeverity::Warning,
+                String::new(),
+            )
+            .with_fix(Some(LintFix {
+                start_byte,
+                end_byte,
+                replacement: replacement.to_string(),
+            }))
+        };
+        let issues = vec![issue(4, 8, "b"), issue(0, 3, "a"), issue(2, 6, "c")];
+
+        let (fixed_code, applied) = apply_fixes("012 4567 9", &issues);
+        assert_eq!(applied, 2);
+        assert_eq!(fixed_code, "a b 9");
+    }
+
+    #[test]
+    fn test_semantic_model_base_classes() {
+        let test_code = r#"
+class_name Player extends CharacterBody2D
+
+
+class Hud extends Control:
+    func update(health):
+        print(health)
+
+
+class Save:
+    extends "res://save.gd"
+"#;
+
+        let semantic = build_semantic_model(test_code);
+        let scope_of = |name: &str| {
+            semantic
+                .symbols()
+                .iter()
+                .find(|symbol| symbol.name == name)
+                .unwrap()
+                .scope
+        };
+        assert_eq!(semantic.base_class(0), Some("CharacterBody2D"));
+        assert_eq!(semantic.base_class(scope_of("health")), Some("Control"));
+        assert_eq!(
+            semantic.base_class(scope_of("Save")),
+            Some("CharacterBody2D")
+        );
+        let save_scope = (0..semantic.scopes().len())
+            .rfind(|&id| semantic.scope(id).kind == crate::linter::semantic::ScopeKind::Class)
+            .unwrap();
+        assert_eq!(semantic.base_class(save_scope), None);
+    }
+
+    #[test]
+    fn test_control_flow_graph() {
+        use crate::linter::control_flow::ControlFlowGraph;
+
+        let test_code = r#"
+func find(items, target):
+    for item in items:
+        if item == target:
+            return item
+        continue
+        print(item)
+    while true:
+        if items.is_empty():
+            break
+        items.pop_back()
+    return null
+
+
+func loop_forever():
+    while true:
+        pass
+    print("never")
+"#;
+
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_gdscript::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(test_code, None).unwrap();
+        let root = tree.root_node();
+        let functions: Vec<tree_sitter::Node> = (0..root.named_child_count())
+            .filter_map(|index| root.named_child(index))
+            .filter(|node| node.kind() == "function_definition")
+            .collect();
+
+        let find = ControlFlowGraph::build(&functions[0], test_code);
+        let unreachable_lines: Vec<usize> = find
+            .unreachable_statements()
+            .iter()
+            .map(|statement| statement.start_position().row + 1)
+            .collect();
+        assert_eq!(unreachable_lines, vec![7]);
+        assert_eq!(find.reachable_returns().count(), 2);
+        assert!(!find.can_reach_end());
+
+        let loop_forever = ControlFlowGraph::build(&functions[1], test_code);
+        let unreachable_lines: Vec<usize> = loop_forever
+            .unreachable_statements()
+            .iter()
+            .map(|statement| statement.start_position().row + 1)
+            .collect();
+        assert_eq!(unreachable_lines, vec![18]);
+        assert!(!loop_forever.can_reach_end());
+    }
+
+    #[test]
+    fn test_function_metrics() {
+        use crate::linter::metrics::compute_metrics;
+
+        let test_code = r#"
+func process_items(items, limit = 10):
+    # Comments aren't statements
+    var count = 0
+    for item in items:
+        if item > limit or item < 0:
+            return -1
+        count += 1
+    var callback = func(value):
+        if value:
+            return value
+    return count if count < limit else limit
+
+
+class Inner:
+    func _init():
+        pass
+"#;
+
+        let metrics = compute_metrics(test_code).unwrap();
+        assert_eq!(metrics.len(), 2);
+        let function = &metrics[0];
+        assert_eq!(function.name, "process_items");
+        assert_eq!((function.line, function.column), (2, 6));
+        assert_eq!(function.arguments, 2);
+        // The lambda's code counts towards the lambda
+        assert_eq!(function.statements, 7);
+        assert_eq!(function.lines, 11);
+        assert_eq!(function.cyclomatic_complexity, 5);
+        assert_eq!(function.max_nesting_depth, 2);
+        assert_eq!(function.returns, 2);
+        assert_eq!(metrics[1].name, "_init");
+    }
+
+    #[test]
+    fn test_function_length_limits() {
+        let test_code = r#"
+func long_function():
+    var a = 1
+    var b = 2
+    var c = 3
+    print(a, b, c)
+"#;
+
+        let config = LinterConfig {
+            enabled_rules: ["function-length".to_string()].into(),
+            max_function_lines: 4,
+            max_function_statements: 3,
+            ..Default::default()
+        };
+        let issues = lint_gdscript_with_config(test_code, "test.gd", &config).unwrap();
+        let messages: Vec<&str> = issues
+            .iter()
+            .filter(|issue| issue.rule == "function-length")
+            .map(|issue| issue.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Function 'long_function' is too long. Found 5 lines, maximum allowed is 4",
+                "Function 'long_function' is too long. Found 4 statements, maximum allowed is 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_class_size_limits() {
+        let test_code = r#"class_name Player
+extends Node
+
+signal died
+signal health_changed
+
+var health = 100
+var speed = 10.0
+@onready var _sprite = $Sprite2D
+
+
+func move():
+    pass
+
+
+func jump():
+    pass
+
+
+func _private_method():
+    pass
+
+
+class Stats:
+    var strength = 1
+
+
+class Inventory:
+    signal item_added
+"#;
+
+        let rules = [
+            "max-file-lines",
+            "max-public-methods",
+            "max-class-variables",
+            "max-inner-classes",
+            "max-signals",
+        ];
+        let config = LinterConfig {
+            enabled_rules: rules.map(String::from).into(),
+            max_file_lines: 20,
+            max_public_methods: 1,
+            max_class_variables: 2,
+            max_inner_classes: 1,
+            max_signals: 1,
+            ..Default::default()
+        };
+        let issues = lint_gdscript_with_config(test_code, "test.gd", &config).unwrap();
+        let messages: Vec<(usize, &str)> = issues
+            .iter()
+            .filter(|issue| rules.contains(&issue.rule.as_str()))
+            .map(|issue| (issue.line, issue.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    1,
+                    "Class 'Player' has too many public methods. Found 2, maximum allowed is 1"
+                ),
+                (
+                    1,
+                    "Class 'Player' has too many member variables. Found 3, maximum allowed is 2"
+                ),
+                (
+                    1,
+                    "Class 'Player' has too many inner classes. Found 2, maximum allowed is 1"
+                ),
+                (
+                    1,
+                    "Class 'Player' has too many signals. Found 2, maximum allowed is 1"
+                ),
+                (
+                    21,
+                    "File is too long. Found 29 lines, maximum allowed is 20"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_static_typing_rules_with_inferred_types() {
+        let test_code = r#"
+func move(speed := 2.0):
+    var distance := speed * 2
+    print(distance)
+"#;
+
+        // The static typing rules are off by default
+        let issues = crate::linter::lint_gdscript(test_code, "test.gd").unwrap();
+        assert!(issues.iter().all(
+            |issue| !issue.rule.starts_with("untyped-") && issue.rule != "missing-return-type"
+        ));
+
+        let config = LinterConfig {
+            enabled_rules: [
+                "untyped-parameter",
+                "untyped-variable",
+                "missing-return-type",
+            ]
+            .map(String::from)
+            .into(),
+            allow_inferred_types: false,
+            ..Default::default()
+        };
+        let issues = lint_gdscript_with_config(test_code, "test.gd", &config).unwrap();
+        let messages: Vec<&str> = issues.iter().map(|issue| issue.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Function 'move' has no static return type. Add one like '-> void'",
+                "Function argument 'speed' infers its type with ':='. Declare its type explicitly",
+                "Variable 'distance' infers its type with ':='. Declare its type explicitly",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_definitions() {
+        let test_code = r#"
+enum { IDLE, RUNNING }
+const IDLE = 0
+var health = 100
+
+
+func _ready():
+    var callback = func(value, value):
+        print(value)
+    callback.call(1, 2)
+
+
+class Player:
+    var health = 10
+
+    func heal():
+        pass
+
+
+class Enemy:
+    var health = 5
+
+    func heal():
+        pass
+"#;
+
+        let config = LinterConfig::default();
+        let issues = lint_gdscript_with_config(test_code, "test.gd", &config).unwrap();
+        // Inner classes have their own members, so `health` and `heal` don't clash
+        let messages: Vec<(usize, &str)> = issues
+            .iter()
+            .filter(|issue| issue.rule == "duplicate-member" || issue.rule == "duplicate-parameter")
+            .map(|issue| (issue.line, issue.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    3,
+                    "Constant 'IDLE' has the same name as the enum member declared on line 2"
+                ),
+                (8, "Function argument 'value' is already declared on line 8"),
+            ]
+        );
+    }
 }
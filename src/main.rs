@@ -2,16 +2,26 @@ use std::{
     env, fs,
     io::{self, IsTerminal, Read, Write},
     path::PathBuf,
+    sync::Arc,
 };
 
 use clap::{CommandFactory, Parser};
 use rayon::prelude::*;
+use similar::TextDiff;
 
 use gdscript_formatter::linter::rule_config::{
-    get_all_rule_names, parse_disabled_rules, validate_rule_names,
+    get_all_rule_names, get_opt_in_rule_names, parse_disabled_rules, validate_rule_names,
 };
 use gdscript_formatter::{
-    FormatterConfig, formatter::format_gdscript_with_config, linter::LinterConfig,
+    AnnotationPlacement, DeclarationCategory, FormatterConfig, GodotVersion, HexDigitCase,
+    RegionOrder, TrailingCommas,
+    code_blocks::{DocumentKind, format_code_blocks_with_config},
+    formatter::format_gdscript_with_config,
+    godot_api::GodotApi,
+    linter::{LinterConfig, metrics::compute_metrics},
+    migrate::migrate_gdscript,
+    reorder::check_declaration_order,
+    resource::{format_resource_with_config, is_resource_file},
 };
 use std::collections::HashSet;
 
@@ -25,6 +35,12 @@ struct FormatterOutput {
     file_path: PathBuf,
     formatted_content: String,
     is_formatted: bool,
+    /// Problems that didn't stop formatting the file, like code blocks with
+    /// syntax errors in documentation files.
+    warnings: Vec<String>,
+    /// Declarations that aren't in the configured order, only collected when
+    /// checking files with --reorder-code.
+    misplaced_declarations: Vec<String>,
 }
 
 #[derive(Parser)]
@@ -46,6 +62,9 @@ struct FormatterOutput {
 struct Args {
     /// The GDScript file(s) to format. If no file paths are provided, the
     /// program reads from standard input and outputs to standard output.
+    ///
+    /// For .tscn and .tres files, the formatter formats the built-in scripts
+    /// and leaves the rest of the file unchanged.
     #[arg(value_name = "FILES")]
     input: Vec<PathBuf>,
 
@@ -65,7 +84,8 @@ struct Args {
     /// Check if FILES are formatted, making no changes.
     ///
     /// Exits with code 0 if the file is already formatted and 1 if it's not
-    /// formatted.
+    /// formatted. With --reorder-code, this also lists the declarations that
+    /// aren't in the configured order.
     #[arg(short, long)]
     check: bool,
 
@@ -91,6 +111,151 @@ struct Args {
     #[arg(long)]
     reorder_code: bool,
 
+    /// Set the order of declarations when reordering code.
+    ///
+    /// A comma-separated list of categories. Categories you leave out go after
+    /// the listed ones, in the default order. For example, to put built-in
+    /// virtual methods like _ready() after the other methods:
+    /// --reorder-order methods,virtual-methods
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "signals,enums,constants,static-variables,export-variables,variables,onready-variables,static-init,static-methods,virtual-methods,methods,inner-classes",
+        value_name = "CATEGORIES"
+    )]
+    reorder_order: Vec<DeclarationCategory>,
+
+    /// Set the built-in virtual methods and their order when reordering code.
+    ///
+    /// A comma-separated list of method names, like _init,_ready,_process.
+    /// Methods you leave out are sorted like other methods. By default, this
+    /// follows the style guide of the targeted Godot version.
+    #[arg(long, value_delimiter = ',', value_name = "METHODS")]
+    virtual_methods: Option<Vec<String>>,
+
+    /// When reordering code, keep pseudo-private declarations (starting with
+    /// an underscore) mixed with public ones instead of moving them after.
+    #[arg(long)]
+    mix_private_members: bool,
+
+    /// Set where reordering code puts #region blocks.
+    ///
+    /// Declarations inside of a region always stay in it and get sorted. With
+    /// "fixed", regions keep their position in the file. With "first-member",
+    /// regions move along with their first declaration.
+    #[arg(long, value_enum, default_value = "fixed", value_name = "MODE")]
+    region_order: RegionOrder,
+
+    /// Don't reorder files with regions that can't be kept intact, like a
+    /// #region without a matching #endregion.
+    #[arg(long)]
+    strict_regions: bool,
+
+    /// List the declarations that reordering code would move and why, making
+    /// no changes to FILES.
+    ///
+    /// Uses the same options as --reorder-code, like --reorder-order.
+    #[arg(long, conflicts_with_all = ["check", "stdout"])]
+    reorder_report: bool,
+
+    /// Normalize number literals.
+    ///
+    /// Lowercases the `0x` and `0b` prefixes and the exponent of number
+    /// literals, and adds the missing zero to floats like `.5` or `5.`.
+    #[arg(long)]
+    normalize_numbers: bool,
+
+    /// Set the letter case of hexadecimal digits.
+    ///
+    /// Has no effect without the --normalize-numbers flag.
+    #[arg(long, value_enum, default_value = "lower", value_name = "CASE")]
+    hex_case: HexDigitCase,
+
+    /// Group the digits of long decimal literals with underscores.
+    ///
+    /// Literals with at least NUM digits before the decimal point are grouped
+    /// by thousands, like `1_000_000`. Has no effect without the
+    /// --normalize-numbers flag.
+    #[arg(long, value_name = "NUM")]
+    group_digits: Option<usize>,
+
+    /// Set the number of blank lines between methods.
+    ///
+    /// This also applies between a method and the class members around it.
+    #[arg(long, default_value = "2", value_name = "NUM")]
+    blank_lines_between_methods: usize,
+
+    /// Set the number of blank lines before and after inner classes.
+    #[arg(long, default_value = "2", value_name = "NUM")]
+    blank_lines_between_classes: usize,
+
+    /// Set the maximum number of consecutive blank lines between properties.
+    ///
+    /// Applies to class members other than methods and inner classes, like
+    /// variables, constants, and signals, which you can separate in groups
+    /// with blank lines.
+    #[arg(long, default_value = "1", value_name = "NUM")]
+    max_blank_lines_between_properties: usize,
+
+    /// Set the number of blank lines after the extends statement at the top
+    /// of the file.
+    #[arg(long, default_value = "1", value_name = "NUM")]
+    blank_lines_after_extends: usize,
+
+    /// Set the maximum number of consecutive blank lines inside function
+    /// bodies.
+    #[arg(long, default_value = "1", value_name = "NUM")]
+    max_blank_lines_in_functions: usize,
+
+    /// Set whether multi-line lists end with a trailing comma.
+    ///
+    /// Applies to arrays, dictionaries, enums, function arguments, and function
+    /// parameters written on multiple lines. Calls to preload() never get a
+    /// trailing comma as the GDScript parser doesn't support it.
+    #[arg(long, value_enum, default_value = "always", value_name = "POLICY")]
+    trailing_commas: TrailingCommas,
+
+    /// Set the maximum line width the formatter aims for.
+    ///
+    /// Method chains like create_tween().set_trans(...).set_ease(...) that make
+    /// a line longer than this get wrapped with one call per line. Tabs count as
+    /// --indent-size columns.
+    #[arg(long, default_value = "100", value_name = "NUM")]
+    line_width: usize,
+
+    /// Normalize comments and reflow doc comments.
+    ///
+    /// Adds a space after the `#` of comments, except for `#!` lines, regions,
+    /// and gdlint directives, and reflows paragraphs of `##` doc comments to
+    /// the --line-width. Code blocks and lines like `@tutorial` stay as they
+    /// are.
+    #[arg(long)]
+    reflow_comments: bool,
+
+    /// Set where to place the annotations of variables.
+    ///
+    /// By default, @export and @onready go on the line of the variable and
+    /// other annotations stay as written. With "inline", annotations like
+    /// @export_range(0, 10) move onto the line of the variable too when the
+    /// line fits in the --line-width. Annotations like @export_group that apply
+    /// to a group of properties always stay on their own line.
+    #[arg(long, value_enum, default_value = "preserve", value_name = "PLACEMENT")]
+    variable_annotations: AnnotationPlacement,
+
+    /// Set where to place the annotations of functions, like @rpc.
+    #[arg(long, value_enum, default_value = "preserve", value_name = "PLACEMENT")]
+    function_annotations: AnnotationPlacement,
+
+    /// Set the major version of Godot the scripts target.
+    ///
+    /// With "3", the formatter follows GDScript 1.0 syntax: keywords like
+    /// onready, export, and setget, and no trailing commas in calls and
+    /// function parameters. Code reordering also follows the Godot 3 style
+    /// guide.
+    #[arg(long, value_enum, default_value = "4", value_name = "VERSION")]
+    godot_version: GodotVersion,
+
     /// Enable safe mode.
     ///
     /// This mode ensures that after formatting, the code still has the same
@@ -105,6 +270,15 @@ struct Args {
     /// lead to syntax changes.
     #[arg(short, long, conflicts_with = "reorder_code")]
     safe: bool,
+
+    /// Also format GDScript code blocks in documentation files.
+    ///
+    /// Formats ```gdscript code blocks in Markdown (.md) files and [codeblock]
+    /// and [gdscript] sections in Godot class reference (.xml) files, keeping
+    /// the indentation of the document. Code blocks with syntax errors are
+    /// reported and left unchanged.
+    #[arg(long)]
+    code_blocks: bool,
 }
 
 #[derive(clap::Subcommand)]
@@ -119,12 +293,130 @@ enum Commands {
             value_name = "RULES"
         )]
         disable: Option<String>,
+        #[arg(
+            long,
+            help = "Enable linting rules that are off by default, like the static typing rules (comma-separated)",
+            value_name = "RULES"
+        )]
+        enable: Option<String>,
         #[arg(long, help = "Maximum line length allowed", default_value = "100")]
         max_line_length: usize,
+        #[arg(
+            long,
+            help = "Maximum number of lines in a function",
+            default_value = "100"
+        )]
+        max_function_lines: usize,
+        #[arg(
+            long,
+            help = "Maximum number of statements in a function",
+            default_value = "50"
+        )]
+        max_function_statements: usize,
+        #[arg(
+            long,
+            help = "Maximum cyclomatic complexity of a function",
+            default_value = "10"
+        )]
+        max_complexity: usize,
+        #[arg(
+            long,
+            help = "Maximum number of nested blocks in a function",
+            default_value = "5"
+        )]
+        max_nesting_depth: usize,
+        #[arg(
+            long,
+            help = "Maximum number of function arguments",
+            default_value = "10"
+        )]
+        max_arguments: usize,
+        #[arg(
+            long,
+            help = "Maximum number of return statements in a function",
+            default_value = "6"
+        )]
+        max_returns: usize,
+        #[arg(
+            long,
+            help = "Maximum number of lines in a file",
+            default_value = "1000"
+        )]
+        max_file_lines: usize,
+        #[arg(
+            long,
+            help = "Maximum number of public methods in a class",
+            default_value = "20"
+        )]
+        max_public_methods: usize,
+        #[arg(
+            long,
+            help = "Maximum number of member variables in a class",
+            default_value = "30"
+        )]
+        max_class_variables: usize,
+        #[arg(
+            long,
+            help = "Maximum number of inner classes in a class",
+            default_value = "5"
+        )]
+        max_inner_classes: usize,
+        #[arg(
+            long,
+            help = "Maximum number of signals in a class",
+            default_value = "10"
+        )]
+        max_signals: usize,
+        #[arg(
+            long,
+            help = "Make the static typing rules report types inferred with :="
+        )]
+        disallow_inferred_types: bool,
+        #[arg(
+            long,
+            help = "Flag syntax that doesn't exist in this major version of Godot",
+            value_enum,
+            default_value = "4",
+            value_name = "VERSION"
+        )]
+        godot_version: GodotVersion,
+        #[arg(
+            long,
+            help = "Use Godot's API from this extension_api.json file instead of the bundled one",
+            value_name = "FILE"
+        )]
+        godot_api: Option<PathBuf>,
         #[arg(long, help = "List all available linting rules")]
         list_rules: bool,
         #[arg(long, help = "Use pretty formatting for lint output")]
         pretty: bool,
+        #[arg(
+            long,
+            help = "Apply the automatic fixes of the issues found, then report the remaining issues"
+        )]
+        fix: bool,
+    },
+    /// Rewrite Godot 3 syntax in GDScript files to Godot 4 syntax
+    ///
+    /// Prints the changes as a diff for review, unless you use --write. Code
+    /// that needs to be migrated by hand gets reported on the standard error.
+    Migrate {
+        #[arg(help = "Input GDScript file(s) to migrate", value_name = "FILES")]
+        input: Vec<PathBuf>,
+        #[arg(long, help = "Overwrite the files instead of printing a diff")]
+        write: bool,
+    },
+    /// Print the size and complexity of each function in GDScript files
+    ///
+    /// For each function, prints the number of statements and lines, the
+    /// cyclomatic complexity, the deepest nesting of blocks, and the number of
+    /// arguments and return statements. These are the values the lint rules
+    /// like cyclomatic-complexity check.
+    Metrics {
+        #[arg(help = "Input GDScript file(s) to measure", value_name = "FILES")]
+        input: Vec<PathBuf>,
+        #[arg(long, help = "Print the metrics as JSON instead of a table")]
+        json: bool,
     },
 }
 
@@ -142,37 +434,77 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if let Some(Commands::Lint {
         input,
         disable,
+        enable,
         max_line_length,
+        max_function_lines,
+        max_function_statements,
+        max_complexity,
+        max_nesting_depth,
+        max_arguments,
+        max_returns,
+        max_file_lines,
+        max_public_methods,
+        max_class_variables,
+        max_inner_classes,
+        max_signals,
+        disallow_inferred_types,
+        godot_version,
+        godot_api,
         list_rules,
         pretty,
+        fix,
     }) = args.command
     {
         if list_rules {
             println!("Available linting rules:");
+            let opt_in_rules = get_opt_in_rule_names();
             for rule in get_all_rule_names() {
-                println!("  {}", rule);
+                if opt_in_rules.contains(&rule) {
+                    println!("  {} (off by default, use --enable)", rule);
+                } else {
+                    println!("  {}", rule);
+                }
             }
             return Ok(());
         }
 
-        let disabled_rules = if let Some(disable_str) = disable {
-            let rules = parse_disabled_rules(&disable_str);
-            if let Err(invalid_rules) = validate_rule_names(&rules) {
-                eprintln!("Error: Invalid rule names: {}", invalid_rules.join(", "));
-                eprintln!("Use --list-rules to see all available rules");
-                std::process::exit(1);
-            }
-            rules
-        } else {
-            HashSet::new()
+        let disabled_rules = parse_rule_names_argument(disable);
+        let enabled_rules = parse_rule_names_argument(enable);
+
+        let godot_api = match godot_api {
+            Some(path) => Arc::new(GodotApi::load(&path)?),
+            None => GodotApi::bundled(),
         };
 
         let linter_config = LinterConfig {
             disabled_rules,
+            enabled_rules,
             max_line_length,
+            max_function_lines,
+            max_function_statements,
+            max_cyclomatic_complexity: max_complexity,
+            max_nesting_depth,
+            max_arguments,
+            max_returns,
+            max_file_lines,
+            max_public_methods,
+            max_class_variables,
+            max_inner_classes,
+            max_signals,
+            allow_inferred_types: !disallow_inferred_types,
+            godot_version,
+            godot_api,
         };
 
-        return run_linter(input, linter_config, pretty);
+        return run_linter(input, linter_config, pretty, fix);
+    }
+
+    if let Some(Commands::Migrate { input, write }) = args.command {
+        return run_migration(input, write);
+    }
+
+    if let Some(Commands::Metrics { input, json }) = args.command {
+        return run_metrics(input, json);
     }
 
     let config = FormatterConfig {
@@ -180,8 +512,31 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         use_spaces: args.use_spaces,
         reorder_code: args.reorder_code,
         safe: args.safe,
+        normalize_numbers: args.normalize_numbers,
+        hex_digit_case: args.hex_case,
+        group_digits: args.group_digits,
+        blank_lines_between_methods: args.blank_lines_between_methods,
+        blank_lines_between_classes: args.blank_lines_between_classes,
+        max_blank_lines_between_properties: args.max_blank_lines_between_properties,
+        blank_lines_after_extends: args.blank_lines_after_extends,
+        max_blank_lines_in_functions: args.max_blank_lines_in_functions,
+        trailing_commas: args.trailing_commas,
+        line_width: args.line_width,
+        reflow_comments: args.reflow_comments,
+        variable_annotations: args.variable_annotations,
+        function_annotations: args.function_annotations,
+        godot_version: args.godot_version,
+        declaration_order: args.reorder_order,
+        virtual_methods: args.virtual_methods,
+        private_members_last: !args.mix_private_members,
+        region_order: args.region_order,
+        strict_regions: args.strict_regions,
     };
 
+    if args.reorder_report {
+        return run_reorder_report(args.input, &config);
+    }
+
     if args.input.is_empty() {
         let mut input_content = String::new();
         io::stdin()
@@ -191,6 +546,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let formatted_content = format_gdscript_with_config(&input_content, &config)?;
 
         if args.check {
+            if args.reorder_code {
+                for declaration in check_declaration_order(&input_content, &config)? {
+                    eprintln!("<stdin>:{}: {}", declaration.line, declaration.message);
+                }
+            }
             if input_content != formatted_content {
                 eprintln!("The input passed via stdin is not formatted");
                 std::process::exit(1);
@@ -207,12 +567,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let input_gdscript_files: Vec<&PathBuf> = args
         .input
         .iter()
-        .filter(|path| path.extension().is_some_and(|ext| ext == "gd"))
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "gd")
+                || is_resource_file(path)
+                || (args.code_blocks && DocumentKind::from_path(path).is_some())
+        })
         .collect();
 
     if input_gdscript_files.is_empty() {
         eprintln!(
-            "Error: No GDScript files found in the arguments provided. Please provide at least one .gd file."
+            "Error: No GDScript files found in the arguments provided. Please provide at least one .gd, .tscn, or .tres file."
         );
         std::process::exit(1);
     }
@@ -237,18 +601,58 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 format!("Failed to read file {}: {}", file_path.display(), error)
             })?;
 
-            let formatted_content =
+            let mut warnings = Vec::new();
+            // Scenes and resources can contain built-in scripts, and
+            // documents can contain code blocks, which we format in place
+            let formatted_content = if let Some(kind) = DocumentKind::from_path(file_path) {
+                let output = format_code_blocks_with_config(&input_content, kind, &config);
+                for error in output.errors {
+                    warnings.push(format!(
+                        "{}:{}: {}, skipping this code block",
+                        file_path.display(),
+                        error.line,
+                        error.message
+                    ));
+                }
+                output.content
+            } else if is_resource_file(file_path) {
+                format_resource_with_config(&input_content, &config).map_err(|error| {
+                    format!("Failed to format file {}: {}", file_path.display(), error)
+                })?
+            } else {
                 format_gdscript_with_config(&input_content, &config).map_err(|error| {
                     format!("Failed to format file {}: {}", file_path.display(), error)
-                })?;
+                })?
+            };
 
             let is_formatted = input_content == formatted_content;
 
+            let mut misplaced_declarations = Vec::new();
+            if args.check
+                && args.reorder_code
+                && file_path.extension().is_some_and(|ext| ext == "gd")
+            {
+                let misplaced =
+                    check_declaration_order(&input_content, &config).map_err(|error| {
+                        format!("Failed to check file {}: {}", file_path.display(), error)
+                    })?;
+                for declaration in misplaced {
+                    misplaced_declarations.push(format!(
+                        "{}:{}: {}",
+                        file_path.display(),
+                        declaration.line,
+                        declaration.message
+                    ));
+                }
+            }
+
             Ok(FormatterOutput {
                 index,
                 file_path: (*file_path).clone(),
                 formatted_content,
                 is_formatted,
+                warnings,
+                misplaced_declarations,
             })
         })
         .collect();
@@ -268,8 +672,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     for output in sorted_outputs {
         match output {
             Ok(output) => {
+                for warning in &output.warnings {
+                    terminal_clear_line();
+                    eprintln!("\rWarning: {}", warning);
+                }
                 if args.check {
-                    if !output.is_formatted {
+                    for declaration in &output.misplaced_declarations {
+                        terminal_clear_line();
+                        eprintln!("\r{}", declaration);
+                    }
+                    if !output.is_formatted || !output.misplaced_declarations.is_empty() {
                         all_formatted = false;
                     }
                 } else if args.stdout {
@@ -320,12 +732,75 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Prints the declarations that reordering code would move in each GDScript
+/// file, or in the code passed via stdin if there are no input files.
+fn run_reorder_report(
+    input_files: Vec<PathBuf>,
+    config: &FormatterConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if input_files.is_empty() {
+        let mut input_content = String::new();
+        io::stdin()
+            .read_to_string(&mut input_content)
+            .map_err(|error| format!("Failed to read from stdin: {}", error))?;
+        for declaration in check_declaration_order(&input_content, config)? {
+            println!("<stdin>:{}: {}", declaration.line, declaration.message);
+        }
+        return Ok(());
+    }
+
+    let mut misplaced_count = 0;
+    for file_path in input_files
+        .iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "gd"))
+    {
+        let input_content = fs::read_to_string(file_path)
+            .map_err(|error| format!("Failed to read file {}: {}", file_path.display(), error))?;
+        let misplaced = check_declaration_order(&input_content, config)
+            .map_err(|error| format!("Failed to check file {}: {}", file_path.display(), error))?;
+        for declaration in &misplaced {
+            println!(
+                "{}:{}: {}",
+                file_path.display(),
+                declaration.line,
+                declaration.message
+            );
+        }
+        misplaced_count += misplaced.len();
+    }
+
+    if misplaced_count == 0 {
+        eprintln!("All declarations are in order");
+    }
+    Ok(())
+}
+
+/// Parses a comma-separated list of rule names passed to `lint`, and exits if
+/// one of them isn't a rule.
+fn parse_rule_names_argument(rules_string: Option<String>) -> HashSet<String> {
+    let Some(rules_string) = rules_string else {
+        return HashSet::new();
+    };
+    let rules = parse_disabled_rules(&rules_string);
+    if let Err(invalid_rules) = validate_rule_names(&rules) {
+        eprintln!("Error: Invalid rule names: {}", invalid_rules.join(", "));
+        eprintln!("Use --list-rules to see all available rules");
+        std::process::exit(1);
+    }
+    rules
+}
+
 fn run_linter(
     input_files: Vec<PathBuf>,
     config: LinterConfig,
     pretty: bool,
+    fix: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut linter = gdscript_formatter::linter::GDScriptLinter::new(config)?;
+    if fix {
+        let fix_count = linter.fix_files(&input_files)?;
+        eprintln!("Applied {} fix(es)", fix_count);
+    }
     let has_issues = linter.lint_files(input_files, pretty)?;
 
     if has_issues {
@@ -335,6 +810,152 @@ fn run_linter(
     Ok(())
 }
 
+fn run_migration(input_files: Vec<PathBuf>, write: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut issue_count = 0;
+    for file_path in input_files {
+        if file_path.extension().is_none_or(|ext| ext != "gd") {
+            eprintln!(
+                "Error: {} is not a GDScript file (must have .gd extension)",
+                file_path.display()
+            );
+            std::process::exit(1);
+        }
+
+        let content = fs::read_to_string(&file_path)
+            .map_err(|error| format!("Failed to read file {}: {}", file_path.display(), error))?;
+        let output = migrate_gdscript(&content)
+            .map_err(|error| format!("Failed to migrate {}: {}", file_path.display(), error))?;
+
+        for issue in &output.issues {
+            eprintln!("{}:{}: {}", file_path.display(), issue.line, issue.message);
+        }
+        issue_count += output.issues.len();
+
+        if output.content == content {
+            continue;
+        }
+        if write {
+            fs::write(&file_path, &output.content).map_err(|error| {
+                format!("Failed to write to file {}: {}", file_path.display(), error)
+            })?;
+        } else {
+            let path = file_path.display().to_string();
+            print!(
+                "{}",
+                TextDiff::from_lines(&content, &output.content)
+                    .unified_diff()
+                    .header(&path, &path)
+            );
+        }
+    }
+
+    if issue_count > 0 {
+        eprintln!("{} construct(s) need to be migrated by hand", issue_count);
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+fn run_metrics(input_files: Vec<PathBuf>, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut rows = Vec::new();
+    for file_path in input_files {
+        if file_path.extension().is_none_or(|ext| ext != "gd") {
+            eprintln!(
+                "Error: {} is not a GDScript file (must have .gd extension)",
+                file_path.display()
+            );
+            std::process::exit(1);
+        }
+
+        let content = fs::read_to_string(&file_path)
+            .map_err(|error| format!("Failed to read file {}: {}", file_path.display(), error))?;
+        let metrics = compute_metrics(&content)
+            .map_err(|error| format!("Failed to measure {}: {}", file_path.display(), error))?;
+        for function in metrics {
+            rows.push((file_path.display().to_string(), function));
+        }
+    }
+
+    if json {
+        let functions: Vec<serde_json::Value> = rows
+            .iter()
+            .map(|(file, function)| {
+                serde_json::json!({
+                    "file": file,
+                    "line": function.line,
+                    "function": function.name,
+                    "statements": function.statements,
+                    "lines": function.lines,
+                    "cyclomatic_complexity": function.cyclomatic_complexity,
+                    "max_nesting_depth": function.max_nesting_depth,
+                    "arguments": function.arguments,
+                    "returns": function.returns,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&functions)?);
+        return Ok(());
+    }
+
+    let headers = [
+        "Location",
+        "Function",
+        "Statements",
+        "Lines",
+        "Complexity",
+        "Nesting",
+        "Arguments",
+        "Returns",
+    ];
+    let table: Vec<[String; 8]> = rows
+        .iter()
+        .map(|(file, function)| {
+            [
+                format!("{}:{}", file, function.line),
+                function.name.clone(),
+                function.statements.to_string(),
+                function.lines.to_string(),
+                function.cyclomatic_complexity.to_string(),
+                function.max_nesting_depth.to_string(),
+                function.arguments.to_string(),
+                function.returns.to_string(),
+            ]
+        })
+        .collect();
+
+    let mut widths = headers.map(str::len);
+    for row in &table {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let format_row = |cells: [&str; 8]| {
+        cells
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(index, (cell, width))| {
+                // Text columns are aligned left and numbers right
+                if index < 2 {
+                    format!("{:<width$}", cell)
+                } else {
+                    format!("{:>width$}", cell)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    println!("{}", format_row(headers));
+    for row in &table {
+        println!("{}", format_row(row.each_ref().map(String::as_str)));
+    }
+
+    Ok(())
+}
+
 fn terminal_clear_line() {
     eprint!("\r{}", " ".repeat(80));
 }
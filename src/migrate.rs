@@ -0,0 +1,470 @@
+//! This module rewrites Godot 3 scripts to Godot 4 syntax.
+//!
+//! It only handles mechanical rewrites that don't need type information:
+//! keywords that became annotations (`tool`, `onready`, `export`), `yield()`
+//! calls, `setget`, and signal connections that use method names. We collect
+//! every rewrite as a byte range replacement from the tree-sitter tree and
+//! apply them all at once, leaving the rest of the file untouched. Code we
+//! can't migrate gets reported so users can port it by hand.
+use tree_sitter::{Node, Parser};
+
+use crate::formatter::collect_nodes_of_kinds;
+
+/// A Godot 3 construct that we couldn't rewrite automatically.
+#[derive(Debug, Clone)]
+pub struct MigrationIssue {
+    /// Line of the construct in the original file, starting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+/// The result of migrating a script.
+pub struct MigrationOutput {
+    /// The migrated code.
+    pub content: String,
+    /// The code that needs to be migrated by hand.
+    pub issues: Vec<MigrationIssue>,
+}
+
+/// Built-in types that `export(Type)` can take as its only argument.
+const EXPORT_TYPES: &[&str] = &[
+    "bool",
+    "int",
+    "float",
+    "String",
+    "Vector2",
+    "Vector3",
+    "Rect2",
+    "Transform",
+    "Transform2D",
+    "Color",
+    "NodePath",
+    "Resource",
+    "Array",
+    "Dictionary",
+    "Texture",
+    "PackedScene",
+];
+
+/// Rewrites the Godot 3 syntax of a GDScript file to Godot 4 syntax.
+pub fn migrate_gdscript(content: &str) -> Result<MigrationOutput, Box<dyn std::error::Error>> {
+    let mut parser = Parser::new();
+    parser.set_language(&tree_sitter_gdscript::LANGUAGE.into())?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or("Failed to parse the GDScript file")?;
+
+    let mut migration = Migration {
+        content,
+        indent_unit: detect_indent_unit(content),
+        replacements: Vec::new(),
+        issues: Vec::new(),
+    };
+
+    let nodes = collect_nodes_of_kinds(
+        tree.root_node(),
+        &[
+            "tool_statement",
+            "onready_variable_statement",
+            "export_variable_statement",
+            "setget",
+            "remote_keyword",
+            "call",
+            "attribute_call",
+        ],
+    );
+    for node in nodes {
+        match node.kind() {
+            "tool_statement" => migration.replace(node, "@tool".to_string()),
+            "onready_variable_statement" => migration.migrate_onready(node),
+            "export_variable_statement" => migration.migrate_export(node),
+            "setget" => migration.migrate_setget(node),
+            "remote_keyword" => migration.report(
+                node,
+                format!(
+                    "'{}' functions need an @rpc annotation in Godot 4, with arguments that depend on how the function is called",
+                    migration.text(node)
+                ),
+            ),
+            _ => migration.migrate_call(node),
+        }
+    }
+
+    let Migration {
+        mut replacements,
+        mut issues,
+        ..
+    } = migration;
+    // Rewrites of nested calls, like a connect() call in the arguments of
+    // yield(), overlap. We keep the outer one, so the inner call stays as is
+    // and we report it: running the command again rewrites it
+    replacements.sort_by_key(|(start, _, _)| *start);
+    let mut migrated = String::with_capacity(content.len());
+    let mut last_end = 0;
+    for (start, end, replacement) in replacements {
+        if start < last_end {
+            let skipped = content[start..end].lines().next().unwrap_or_default();
+            issues.push(MigrationIssue {
+                line: content[..start].matches('\n').count() + 1,
+                message: format!(
+                    "Couldn't rewrite '{}' as it's inside code that was rewritten too. Run migrate again to rewrite it",
+                    skipped.trim()
+                ),
+            });
+            continue;
+        }
+        migrated.push_str(&content[last_end..start]);
+        migrated.push_str(&replacement);
+        last_end = end;
+    }
+    migrated.push_str(&content[last_end..]);
+    issues.sort_by_key(|issue| issue.line);
+
+    Ok(MigrationOutput {
+        content: migrated,
+        issues,
+    })
+}
+
+struct Migration<'a> {
+    content: &'a str,
+    /// The indentation of one level in the file, used to move `setget` onto
+    /// its own line.
+    indent_unit: &'static str,
+    replacements: Vec<(usize, usize, String)>,
+    issues: Vec<MigrationIssue>,
+}
+
+impl<'a> Migration<'a> {
+    fn text(&self, node: Node) -> &'a str {
+        &self.content[node.byte_range()]
+    }
+
+    fn replace(&mut self, node: Node, replacement: String) {
+        self.replacements
+            .push((node.start_byte(), node.end_byte(), replacement));
+    }
+
+    fn report(&mut self, node: Node, message: String) {
+        self.issues.push(MigrationIssue {
+            line: node.start_position().row + 1,
+            message,
+        });
+    }
+
+    /// `onready var label = $Label` becomes `@onready var label = $Label`.
+    fn migrate_onready(&mut self, node: Node) {
+        if self.text(node).starts_with("onready") {
+            let start = node.start_byte();
+            self.replacements
+                .push((start, start + "onready".len(), "@onready".to_string()));
+        }
+    }
+
+    /// Rewrites `export` and its type hints to the matching Godot 4 export
+    /// annotation, like `export(int, 0, 10) var speed` to
+    /// `@export_range(0, 10) var speed: int`.
+    fn migrate_export(&mut self, node: Node) {
+        let Some(var_keyword) = find_child_of_kind(node, "var") else {
+            return;
+        };
+        let arguments: Vec<&str> = find_child_of_kind(node, "arguments")
+            .map(|arguments| {
+                let mut cursor = arguments.walk();
+                arguments
+                    .named_children(&mut cursor)
+                    .filter(|argument| argument.kind() != "comment")
+                    .map(|argument| self.text(argument))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let Some((annotation, hint_type)) = export_annotation(&arguments) else {
+            self.report(
+                node,
+                format!(
+                    "Can't convert the hints of 'export({})', use the matching @export_* annotation",
+                    arguments.join(", ")
+                ),
+            );
+            return;
+        };
+
+        // Keywords between `export(...)` and `var`, like `onready`, stay as
+        // they are
+        let keywords_start = find_child_of_kind(node, "arguments")
+            .map_or(node.start_byte() + "export".len(), |arguments| {
+                arguments.end_byte()
+            });
+        let keywords = self.content[keywords_start..var_keyword.start_byte()].trim();
+        let keywords = match keywords {
+            "" => String::new(),
+            "onready" => "@onready ".to_string(),
+            other => format!("{} ", other),
+        };
+        self.replacements.push((
+            node.start_byte(),
+            var_keyword.start_byte(),
+            format!("{} {}", annotation, keywords),
+        ));
+
+        // Godot 4 exports need a type, so we add the one from the hints if
+        // the variable doesn't have one
+        if let Some(hint_type) = hint_type
+            && node.child_by_field_name("type").is_none()
+            && let Some(name) = node.child_by_field_name("name")
+        {
+            let end = name.end_byte();
+            self.replacements
+                .push((end, end, format!(": {}", hint_type)));
+        }
+    }
+
+    /// `var health = 100 setget set_health, get_health` becomes a property
+    /// with `set = set_health, get = get_health` on the next line.
+    fn migrate_setget(&mut self, node: Node) {
+        let text = self.text(node);
+        let Some(functions) = text.strip_prefix("setget") else {
+            // This is already Godot 4 property syntax
+            return;
+        };
+        let (setter, getter) = functions.split_once(',').unwrap_or((functions, ""));
+        let accessors: Vec<String> = [("set", setter.trim()), ("get", getter.trim())]
+            .into_iter()
+            .filter(|(_, function)| !function.is_empty())
+            .map(|(accessor, function)| format!("{} = {}", accessor, function))
+            .collect();
+        if accessors.is_empty() {
+            return;
+        }
+
+        let line_start = self.content[..node.start_byte()]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        let line = &self.content[line_start..];
+        let indent = &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+        // We replace the whitespace before `setget` too
+        let start = self.content[..node.start_byte()]
+            .trim_end_matches([' ', '\t'])
+            .len();
+        self.replacements.push((
+            start,
+            node.end_byte(),
+            format!(":\n{}{}{}", indent, self.indent_unit, accessors.join(", ")),
+        ));
+    }
+
+    /// Rewrites `yield()` calls and signal connections that use method names.
+    fn migrate_call(&mut self, node: Node) {
+        let Some(function) = node.child(0) else {
+            return;
+        };
+        let Some(arguments) = find_child_of_kind(node, "arguments") else {
+            return;
+        };
+        let mut cursor = arguments.walk();
+        let arguments: Vec<Node> = arguments
+            .named_children(&mut cursor)
+            .filter(|argument| argument.kind() != "comment")
+            .collect();
+
+        match self.text(function) {
+            "yield" if node.kind() == "call" => self.migrate_yield(node, &arguments),
+            "connect" | "disconnect" | "is_connected" => self.migrate_connection(node, &arguments),
+            _ => {}
+        }
+    }
+
+    /// `yield(obj, "signal")` becomes `await obj.signal`, and
+    /// `yield(function(), "completed")` becomes `await function()`.
+    fn migrate_yield(&mut self, node: Node, arguments: &[Node]) {
+        let signal = arguments
+            .get(1)
+            .and_then(|signal| self.string_value(*signal));
+        let (Some(object), Some(signal), 2) = (arguments.first(), signal, arguments.len()) else {
+            self.report(
+                node,
+                "Can't convert this yield() call, use await with a signal or a coroutine call"
+                    .to_string(),
+            );
+            return;
+        };
+
+        let object = self.text(*object);
+        let awaited = if signal == "completed" && object.ends_with(')') {
+            object.to_string()
+        } else if object == "self" {
+            signal.to_string()
+        } else {
+            format!("{}.{}", object, signal)
+        };
+        self.replace(node, format!("await {}", awaited));
+    }
+
+    /// `connect("signal", target, "method", [binds], flags)` becomes
+    /// `connect("signal", target.method.bind(binds), flags)`. We rewrite
+    /// `disconnect()` and `is_connected()` calls the same way.
+    fn migrate_connection(&mut self, node: Node, arguments: &[Node]) {
+        // With fewer arguments, the call already uses a Callable
+        if arguments.len() < 3 {
+            return;
+        }
+        let Some(method) = self.string_value(arguments[2]) else {
+            self.report(
+                node,
+                "Can't convert a connection with a method name that isn't a string literal, use a Callable".to_string(),
+            );
+            return;
+        };
+
+        let target = self.text(arguments[1]);
+        let mut callable = if target == "self" {
+            method.to_string()
+        } else {
+            format!("{}.{}", target, method)
+        };
+        if let Some(binds) = arguments.get(3) {
+            let binds_text = self.text(*binds);
+            match binds_text
+                .strip_prefix('[')
+                .and_then(|binds| binds.strip_suffix(']'))
+            {
+                Some(binds) if binds.trim().is_empty() => {}
+                Some(binds) => {
+                    let binds = binds.trim().trim_end_matches(',').trim_end();
+                    callable = format!("{}.bind({})", callable, binds);
+                }
+                None => {
+                    self.report(
+                        node,
+                        "Can't convert connection binds that aren't an array literal, use Callable.bindv()".to_string(),
+                    );
+                    return;
+                }
+            }
+        }
+
+        self.replacements.push((
+            arguments[1].start_byte(),
+            arguments[arguments.len().min(4) - 1].end_byte(),
+            callable,
+        ));
+    }
+
+    /// Returns the content of a string literal without quotes, if the node is
+    /// a simple string literal.
+    fn string_value(&self, node: Node) -> Option<&'a str> {
+        if node.kind() != "string" {
+            return None;
+        }
+        let text = self.text(node);
+        let value = text
+            .strip_prefix('"')
+            .and_then(|text| text.strip_suffix('"'))
+            .or_else(|| {
+                text.strip_prefix('\'')
+                    .and_then(|text| text.strip_suffix('\''))
+            })?;
+        value
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_')
+            .then_some(value)
+    }
+}
+
+/// Returns the Godot 4 annotation matching the arguments of a Godot 3
+/// `export(...)` keyword, and the type the variable needs, if any. Returns None
+/// for hints we don't know how to convert.
+fn export_annotation(arguments: &[&str]) -> Option<(String, Option<String>)> {
+    let Some((hint_type, hints)) = arguments.split_first() else {
+        return Some(("@export".to_string(), None));
+    };
+    let hint_type = hint_type.to_string();
+    let is_number = |text: &&str| text.trim_start_matches('-').parse::<f64>().is_ok();
+    let is_string = |text: &&str| text.starts_with('"') || text.starts_with('\'');
+
+    let annotation = match (hint_type.as_str(), hints) {
+        (_, []) => "@export".to_string(),
+        ("String", ["FILE"]) => "@export_file".to_string(),
+        ("String", ["DIR"]) => "@export_dir".to_string(),
+        ("String", ["FILE", "GLOBAL"]) | ("String", ["GLOBAL", "FILE"]) => {
+            "@export_global_file".to_string()
+        }
+        ("String", ["DIR", "GLOBAL"]) | ("String", ["GLOBAL", "DIR"]) => {
+            "@export_global_dir".to_string()
+        }
+        ("String", ["FILE", filter]) => format!("@export_file({})", filter),
+        ("String", ["MULTILINE"]) => "@export_multiline".to_string(),
+        ("Color", ["RGB"]) => "@export_color_no_alpha".to_string(),
+        ("int", ["FLAGS", flags @ ..]) if !flags.is_empty() && flags.iter().all(is_string) => {
+            format!("@export_flags({})", flags.join(", "))
+        }
+        ("int", ["LAYERS_2D_PHYSICS"]) => "@export_flags_2d_physics".to_string(),
+        ("int", ["LAYERS_2D_RENDER"]) => "@export_flags_2d_render".to_string(),
+        ("int", ["LAYERS_3D_PHYSICS"]) => "@export_flags_3d_physics".to_string(),
+        ("int", ["LAYERS_3D_RENDER"]) => "@export_flags_3d_render".to_string(),
+        ("int" | "String", values) if values.iter().all(is_string) => {
+            format!("@export_enum({})", values.join(", "))
+        }
+        ("int" | "float", range)
+            if (2..=3).contains(&range.len()) && range.iter().all(is_number) =>
+        {
+            format!("@export_range({})", range.join(", "))
+        }
+        ("float", ["EXP", range @ ..])
+            if (2..=3).contains(&range.len()) && range.iter().all(is_number) =>
+        {
+            format!("@export_range({}, \"exp\")", range.join(", "))
+        }
+        ("float", ["EASE"]) => "@export_exp_easing".to_string(),
+        _ => return None,
+    };
+
+    // A single argument can be a class name, like `export(PackedScene)`
+    let is_type = EXPORT_TYPES.contains(&hint_type.as_str())
+        || hint_type.starts_with(|c: char| c.is_ascii_uppercase());
+    if !is_type {
+        return None;
+    }
+    Some((annotation, Some(hint_type)))
+}
+
+fn find_child_of_kind<'a>(node: Node<'a>, kind: &str) -> Option<Node<'a>> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .find(|child| child.kind() == kind)
+}
+
+/// Returns the indentation of the first indented line of the file, or a tab.
+fn detect_indent_unit(content: &str) -> &'static str {
+    let first_indented_line = content
+        .lines()
+        .find(|line| line.starts_with([' ', '\t']) && !line.trim().is_empty());
+    match first_indented_line {
+        Some(line) if line.starts_with(' ') => "    ",
+        _ => "\t",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_nested_rewrites() {
+        let code =
+            "func _ready():\n\tyield(connect(\"died\", self, \"_on_died\"), \"completed\")\n";
+        let output = migrate_gdscript(code).unwrap();
+        assert_eq!(
+            output.content,
+            "func _ready():\n\tawait connect(\"died\", self, \"_on_died\")\n"
+        );
+        assert_eq!(output.issues.len(), 1);
+        assert_eq!(output.issues[0].line, 2);
+        assert!(
+            output.issues[0]
+                .message
+                .starts_with("Couldn't rewrite 'connect(\"died\", self, \"_on_died\")'")
+        );
+    }
+}
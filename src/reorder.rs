@@ -7,21 +7,123 @@
 //!
 //! We assume that you won't run this on every save, but rather manually using
 //! a code editor command or task when you're met with a messy file.
-use tree_sitter::{Node, Query, QueryCursor, StreamingIterator, Tree};
+use tree_sitter::{Node, Tree};
+
+use crate::{DeclarationCategory, FormatterConfig, GodotVersion, RegionOrder};
 
 /// This method parses the GDScript content, extracts top-level elements,
-/// and reorders them according to the GDScript style guide.
+/// and reorders them according to the GDScript style guide. The members of
+/// inner classes get reordered the same way.
 pub fn reorder_gdscript_elements(
     tree: &Tree,
     content: &str,
+    config: &FormatterConfig,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let virtual_methods = get_virtual_methods(config);
+    reorder_class_members(tree.root_node(), content, config, &virtual_methods)
+}
+
+/// A declaration that isn't in the configured order.
+#[derive(Debug, Clone)]
+pub struct MisplacedDeclaration {
+    /// Line of the declaration, starting from 1.
+    pub line: usize,
+    /// Explains where the declaration should go, like "onready var `_sprite`
+    /// must come after regular variables".
+    pub message: String,
+}
+
+/// Returns the declarations that reordering code would move, without changing
+/// the code. This checks the file's top-level declarations and the members of
+/// inner classes. `root` is the root node of the parsed file.
+pub fn find_misplaced_declarations(
+    root: Node,
+    content: &str,
+    config: &FormatterConfig,
+) -> Result<Vec<MisplacedDeclaration>, Box<dyn std::error::Error>> {
+    let virtual_methods = get_virtual_methods(config);
+    let mut misplaced = Vec::new();
+    find_misplaced_class_members(root, content, config, &virtual_methods, &mut misplaced)?;
+    misplaced.sort_by_key(|declaration| declaration.line);
+    Ok(misplaced)
+}
+
+/// Parses the GDScript content and returns the declarations that reordering
+/// code would move. See `find_misplaced_declarations()`.
+pub fn check_declaration_order(
+    content: &str,
+    config: &FormatterConfig,
+) -> Result<Vec<MisplacedDeclaration>, Box<dyn std::error::Error>> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&tree_sitter_gdscript::LANGUAGE.into())?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or("failed to parse the GDScript code")?;
+    find_misplaced_declarations(tree.root_node(), content, config)
+}
+
+/// Returns the built-in virtual methods in the order they should appear.
+fn get_virtual_methods(config: &FormatterConfig) -> Vec<&str> {
+    match &config.virtual_methods {
+        Some(methods) => methods.iter().map(String::as_str).collect(),
+        None => builtin_virtual_methods(config.godot_version).to_vec(),
+    }
+}
+
+/// Returns the virtual methods of Godot's built-in classes, which the engine
+/// calls, for the given version of Godot.
+pub(crate) fn builtin_virtual_methods(godot_version: GodotVersion) -> &'static [&'static str] {
+    match godot_version {
+        GodotVersion::Godot3 => BUILTIN_VIRTUAL_METHODS_GODOT_3,
+        GodotVersion::Godot4 => BUILTIN_VIRTUAL_METHODS,
+    }
+}
+
+/// Reorders the members of a class, which are either the top-level elements of
+/// the file (`source` node) or the members of an inner class (`class_body`
+/// node), and returns the reordered code.
+fn reorder_class_members(
+    parent: Node,
+    content: &str,
+    config: &FormatterConfig,
+    virtual_methods: &[&str],
 ) -> Result<String, Box<dyn std::error::Error>> {
-    let tokens = extract_tokens_to_reorder(tree, content)?;
-    let ordered_elements = sort_gdscript_tokens(tokens);
-    let reordered_content = build_reordered_code(ordered_elements, content);
+    let tokens = extract_tokens_to_reorder(parent, content, config, virtual_methods)?;
+    let ordered_elements = sort_gdscript_tokens_with_regions(tokens, config)?;
+    let reordered_content = build_reordered_code(ordered_elements, content, config);
 
     Ok(reordered_content)
 }
 
+/// Returns the text of an inner class with its members reordered.
+fn reorder_inner_class(
+    node: Node,
+    content: &str,
+    config: &FormatterConfig,
+    virtual_methods: &[&str],
+) -> Result<String, Box<dyn std::error::Error>> {
+    let text = node.utf8_text(content.as_bytes())?;
+    let mut cursor = node.walk();
+    let Some(body) = node
+        .children(&mut cursor)
+        .find(|child| child.kind() == "class_body")
+    else {
+        return Ok(text.to_string());
+    };
+
+    // The body starts after the indentation of its first line, and the
+    // reordered members are indented, so we strip the first line's indentation
+    let reordered_body = reorder_class_members(body, content, config, virtual_methods)?;
+    let body_start = body.start_byte() - node.start_byte();
+    let body_end = body.end_byte() - node.start_byte();
+    Ok(format!(
+        "{}{}{}",
+        &text[..body_start],
+        reordered_body.trim(),
+        &text[body_end..]
+    ))
+}
+
 /// This struct is used to hold an element along with its associated comments
 /// and original text so we can precisely reconstruct it, and also when we move
 /// functions etc. their docstrings and comments come along.
@@ -33,6 +135,14 @@ pub struct GDScriptTokensWithComments {
     pub original_text: String,
     pub start_byte: usize,
     pub end_byte: usize,
+    /// True if a blank line separates this declaration from the previous one
+    /// of the same kind, like two groups of constants. We keep these groups
+    /// apart when rebuilding the code.
+    pub blank_line_before: bool,
+    /// The index of the `@export_group`, `@export_subgroup`, or
+    /// `@export_category` section an exported variable belongs to, counting
+    /// from 1. Exported variables before the first section are in section 0.
+    pub export_group: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -55,6 +165,12 @@ pub enum GDScriptTokenKind {
     // This is for cases like new syntax as it comes out - in general, elements
     // we don't recognize and we don't want to mess up
     Unknown(String),
+    // The #region and #endregion markers around a group of declarations
+    RegionStart(String),
+    RegionEnd(String),
+    // Comments separated from the declarations around them by blank lines.
+    // They keep their position in the list of declarations when reordering.
+    FloatingComment(String),
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Ord, Eq)]
@@ -96,10 +212,18 @@ const BUILTIN_VIRTUAL_METHODS: &[&str] = &[
     "_ready",
     "_process",
     "_physics_process",
+    "_integrate_forces",
     "_exit_tree",
     "_input",
+    "_shortcut_input",
     "_unhandled_input",
+    "_unhandled_key_input",
     "_gui_input",
+    "_has_point",
+    "_get_drag_data",
+    "_can_drop_data",
+    "_drop_data",
+    "_make_custom_tooltip",
     "_draw",
     "_notification",
     "_get_configuration_warnings",
@@ -112,28 +236,90 @@ const BUILTIN_VIRTUAL_METHODS: &[&str] = &[
     "_to_string",
 ];
 
+/// Built-in virtual methods of Godot 3 in the order they should appear. The
+/// Godot 3 style guide puts `_ready` right after `_init`.
+const BUILTIN_VIRTUAL_METHODS_GODOT_3: &[&str] = &[
+    "_init",
+    "_ready",
+    "_enter_tree",
+    "_process",
+    "_physics_process",
+    "_integrate_forces",
+    "_exit_tree",
+    "_input",
+    "_unhandled_input",
+    "_unhandled_key_input",
+    "_gui_input",
+    "_has_point",
+    "_get_drag_data",
+    "_can_drop_data",
+    "_drop_data",
+    "_make_custom_tooltip",
+    "_draw",
+    "_notification",
+    "_get_configuration_warning",
+    "_get_property_list",
+    "_get",
+    "_set",
+    "_to_string",
+];
+
 impl GDScriptTokenKind {
     /// Returns the ordering priority for this kind of declaration. The lower the
-    /// number, the higher the priority.
-    pub fn get_priority(&self) -> u8 {
+    /// number, the higher the priority. The header of the file always comes
+    /// first, followed by the categories of declarations in the given order.
+    pub fn get_priority(&self, declaration_order: &[DeclarationCategory]) -> u8 {
         match self {
             GDScriptTokenKind::ClassAnnotation(_) => 1,
             GDScriptTokenKind::ClassName(_) => 2,
             GDScriptTokenKind::Extends(_) => 3,
             GDScriptTokenKind::Docstring(_) => 4,
-            GDScriptTokenKind::Signal(_, _) => 5,
-            GDScriptTokenKind::Enum(_, _) => 6,
-            GDScriptTokenKind::Constant(_, _) => 7,
-            GDScriptTokenKind::StaticVariable(_, _) => 8,
-            GDScriptTokenKind::ExportVariable(_, _) => 9,
-            GDScriptTokenKind::RegularVariable(_, _) => 10,
-            GDScriptTokenKind::OnReadyVariable(_, _) => 11,
-            GDScriptTokenKind::Method(_, MethodType::StaticInit, _) => 12,
-            GDScriptTokenKind::Method(_, MethodType::StaticFunction, _) => 13,
-            GDScriptTokenKind::Method(_, MethodType::BuiltinVirtual(_), _) => 14,
-            GDScriptTokenKind::Method(_, MethodType::Custom, _) => 15,
-            GDScriptTokenKind::InnerClass(_, _) => 16,
-            GDScriptTokenKind::Unknown(_) => 255,
+            GDScriptTokenKind::Unknown(_)
+            | GDScriptTokenKind::RegionStart(_)
+            | GDScriptTokenKind::RegionEnd(_)
+            | GDScriptTokenKind::FloatingComment(_) => 255,
+            _ => {
+                let category = self.get_category();
+                // Categories missing from the order go after the listed
+                // ones, in the default order
+                let position = declaration_order
+                    .iter()
+                    .position(|listed| *listed == category)
+                    .unwrap_or_else(|| {
+                        let default_position = DeclarationCategory::DEFAULT_ORDER
+                            .iter()
+                            .position(|listed| *listed == category)
+                            .unwrap_or_default();
+                        declaration_order.len() + default_position
+                    });
+                5 + position as u8
+            }
+        }
+    }
+
+    /// Returns the category of a class member declaration. Elements of the
+    /// file header and unknown elements don't belong to a category, so we
+    /// sort them like methods.
+    fn get_category(&self) -> DeclarationCategory {
+        match self {
+            GDScriptTokenKind::Signal(_, _) => DeclarationCategory::Signals,
+            GDScriptTokenKind::Enum(_, _) => DeclarationCategory::Enums,
+            GDScriptTokenKind::Constant(_, _) => DeclarationCategory::Constants,
+            GDScriptTokenKind::StaticVariable(_, _) => DeclarationCategory::StaticVariables,
+            GDScriptTokenKind::ExportVariable(_, _) => DeclarationCategory::ExportVariables,
+            GDScriptTokenKind::RegularVariable(_, _) => DeclarationCategory::Variables,
+            GDScriptTokenKind::OnReadyVariable(_, _) => DeclarationCategory::OnreadyVariables,
+            GDScriptTokenKind::Method(_, MethodType::StaticInit, _) => {
+                DeclarationCategory::StaticInit
+            }
+            GDScriptTokenKind::Method(_, MethodType::StaticFunction, _) => {
+                DeclarationCategory::StaticMethods
+            }
+            GDScriptTokenKind::Method(_, MethodType::BuiltinVirtual(_), _) => {
+                DeclarationCategory::VirtualMethods
+            }
+            GDScriptTokenKind::InnerClass(_, _) => DeclarationCategory::InnerClasses,
+            _ => DeclarationCategory::Methods,
         }
     }
 
@@ -188,36 +374,34 @@ fn get_token_kind(token_kind: &GDScriptTokenKind) -> TokenKind {
         GDScriptTokenKind::Method(_, _, _) => TokenKind::Method,
         GDScriptTokenKind::InnerClass(_, _) => TokenKind::InnerClass,
         GDScriptTokenKind::Unknown(_) => TokenKind::Method,
+        GDScriptTokenKind::RegionStart(_)
+        | GDScriptTokenKind::RegionEnd(_)
+        | GDScriptTokenKind::FloatingComment(_) => TokenKind::Method,
     }
 }
 
-/// Extracts all top-level elements from the parsed tree.
+/// Extracts all the elements of a class from the parsed tree: the top-level
+/// elements of the file, or the members of an inner class.
 fn extract_tokens_to_reorder(
-    tree: &Tree,
+    parent: Node,
     content: &str,
+    config: &FormatterConfig,
+    virtual_methods: &[&str],
 ) -> Result<Vec<GDScriptTokensWithComments>, Box<dyn std::error::Error>> {
-    let root = tree.root_node();
     let mut elements = Vec::new();
 
-    // This query covers all top-level elements (direct children of source)
-    // We need to capture everything so nothing gets lost
-    let query_str = r#"
-        (source (_) @element)
-    "#;
-
-    let query = Query::new(&tree_sitter_gdscript::LANGUAGE.into(), query_str)?;
-    let mut cursor = QueryCursor::new();
-    let mut matches = cursor.matches(&query, root, content.as_bytes());
-
+    // We need to capture all the direct children of the class so nothing gets
+    // lost
     let mut all_nodes = Vec::new();
-
-    // Collect all the nodes and their positions
-    while let Some(m) = matches.next() {
-        for capture in m.captures {
-            let node = capture.node;
-            let text = node.utf8_text(content.as_bytes())?;
-            all_nodes.push((node, text.to_string()));
-        }
+    let mut cursor = parent.walk();
+    for node in parent.named_children(&mut cursor) {
+        // We reorder the members of inner classes recursively
+        let text = if node.kind() == "class_definition" {
+            reorder_inner_class(node, content, config, virtual_methods)?
+        } else {
+            node.utf8_text(content.as_bytes())?.to_string()
+        };
+        all_nodes.push((node, text));
     }
 
     // First we process the top of the node tree. We look for the class docstring.
@@ -231,15 +415,7 @@ fn extract_tokens_to_reorder(
     // We need to check if the comments are contiguous with the declaration they are
     // attached to.
 
-    // Find the byte position of the first class_name or extends statement
-    // Any annotations before this position are class-level annotations
-    let first_class_declaration_byte = all_nodes
-        .iter()
-        .find(|(node, _)| {
-            node.kind() == "class_name_statement" || node.kind() == "extends_statement"
-        })
-        .map(|(node, _)| node.start_byte())
-        .unwrap_or(usize::MAX);
+    let first_class_declaration_byte = find_first_class_declaration_byte(parent);
 
     let mut class_docstring_comments = Vec::new();
     let mut class_docstring_comments_rows = Vec::new();
@@ -286,8 +462,13 @@ fn extract_tokens_to_reorder(
     // collected comments/annotations to that declaration.
     for (node, text) in &all_nodes {
         let is_before_class_declaration = node.start_byte() < first_class_declaration_byte;
-        let reorderable_element =
-            classify_element(*node, text, content, is_before_class_declaration)?;
+        let reorderable_element = classify_element(
+            *node,
+            text,
+            content,
+            is_before_class_declaration,
+            virtual_methods,
+        )?;
         classified_elements.push(ClassifiedElement {
             node: *node,
             text: text.clone(),
@@ -295,16 +476,18 @@ fn extract_tokens_to_reorder(
         });
     }
     let mut pending_comments = Vec::new();
+    // The first and last row of each pending comment, to find the comments
+    // that aren't right above the next element
+    let mut pending_comment_rows = Vec::new();
     let mut pending_annotations = Vec::new();
+    let mut pending_annotations_row = None;
+    // An @export_group annotation and the comments above it, which go with
+    // the next exported variable
+    let mut pending_export_group = Vec::new();
+    let mut export_group = 0;
+    let mut previous_end_row = None;
     let mut found_extends_declaration = false;
     let mut class_docstring_attached = false;
-    // TODO: Handle multiple #region/#endregion pairs properly
-    // Nathan: For now we just attach the last #endregion to the most recent function
-    // that has a #region comment, to handle the most common use case
-    // Regions generally are tricky to reorder as they can span multiple
-    // functions that should be reordered. In those cases I would recommend users not to
-    // use regions though, or not to use the reorder feature
-    let mut region_end_comment = None;
 
     for classified in classified_elements {
         let node = classified.node;
@@ -318,18 +501,68 @@ fn extract_tokens_to_reorder(
                     continue;
                 } else {
                     pending_comments.push(text);
+                    pending_comment_rows.push((node.start_position().row, get_end_row(node)));
                 }
             }
-            "region_start" => {
-                pending_comments.push(text);
+            // Region markers become elements of their own, so we can keep the
+            // declarations of each region together when sorting. Comments at
+            // the end of a region stay in it.
+            "region_start" | "region_end" => {
+                detach_comments(
+                    &mut pending_comments,
+                    &mut pending_comment_rows,
+                    node.start_position().row,
+                    previous_end_row,
+                    &mut elements,
+                );
+                let token_kind = if node.kind() == "region_start" {
+                    GDScriptTokenKind::RegionStart(text.clone())
+                } else {
+                    GDScriptTokenKind::RegionEnd(text.clone())
+                };
+                elements.push(GDScriptTokensWithComments {
+                    token_kind,
+                    attached_comments: pending_comments.clone(),
+                    trailing_comments: Vec::new(),
+                    original_text: text,
+                    start_byte: node.start_byte(),
+                    end_byte: node.end_byte(),
+                    blank_line_before: false,
+                    export_group: 0,
+                });
+                pending_comments.clear();
+                pending_comment_rows.clear();
+                previous_end_row = Some(get_end_row(node));
             }
-            "region_end" => {
-                region_end_comment = Some(text.clone());
+            // Export groups govern the exported variables below them, so they
+            // move with the next one, and later exports stay in their group
+            "annotation" if is_export_grouping_annotation(&text) => {
+                detach_comments(
+                    &mut pending_comments,
+                    &mut pending_comment_rows,
+                    node.start_position().row,
+                    previous_end_row,
+                    &mut elements,
+                );
+                pending_export_group.append(&mut pending_comments);
+                pending_export_group.append(&mut pending_annotations);
+                pending_export_group.push(text);
+                pending_comment_rows.clear();
+                pending_annotations_row = None;
+                export_group += 1;
+                previous_end_row = None;
             }
             "annotation" => {
                 if let Some(element) = reorderable_element {
                     match element {
                         GDScriptTokenKind::ClassAnnotation(_) => {
+                            detach_comments(
+                                &mut pending_comments,
+                                &mut pending_comment_rows,
+                                node.start_position().row,
+                                previous_end_row,
+                                &mut elements,
+                            );
                             elements.push(GDScriptTokensWithComments {
                                 token_kind: element,
                                 attached_comments: Vec::new(),
@@ -337,18 +570,30 @@ fn extract_tokens_to_reorder(
                                 original_text: text,
                                 start_byte: node.start_byte(),
                                 end_byte: node.end_byte(),
+                                blank_line_before: false,
+                                export_group: 0,
                             });
+                            previous_end_row = Some(get_end_row(node));
                         }
                         _ => {
+                            pending_annotations_row.get_or_insert(node.start_position().row);
                             pending_annotations.push(text);
                         }
                     }
                 } else {
+                    pending_annotations_row.get_or_insert(node.start_position().row);
                     pending_annotations.push(text);
                 }
             }
             "class_name_statement" => {
                 if let Some(element) = reorderable_element {
+                    detach_comments(
+                        &mut pending_comments,
+                        &mut pending_comment_rows,
+                        node.start_position().row,
+                        previous_end_row,
+                        &mut elements,
+                    );
                     // Don't attach class docstring to class_name, save it for extends
                     let mut non_docstring_comments = Vec::new();
                     for comment in &pending_comments {
@@ -363,14 +608,26 @@ fn extract_tokens_to_reorder(
                         original_text: text,
                         start_byte: node.start_byte(),
                         end_byte: node.end_byte(),
+                        blank_line_before: false,
+                        export_group: 0,
                     });
                     pending_comments.clear();
+                    pending_comment_rows.clear();
                     pending_annotations.clear();
+                    pending_annotations_row = None;
+                    previous_end_row = Some(get_end_row(node));
                 }
             }
             "extends_statement" => {
                 found_extends_declaration = true;
                 if let Some(element) = reorderable_element {
+                    detach_comments(
+                        &mut pending_comments,
+                        &mut pending_comment_rows,
+                        node.start_position().row,
+                        previous_end_row,
+                        &mut elements,
+                    );
                     elements.push(GDScriptTokensWithComments {
                         token_kind: element,
                         attached_comments: pending_comments.clone(),
@@ -378,9 +635,14 @@ fn extract_tokens_to_reorder(
                         original_text: text,
                         start_byte: node.start_byte(),
                         end_byte: node.end_byte(),
+                        blank_line_before: false,
+                        export_group: 0,
                     });
                     pending_comments.clear();
+                    pending_comment_rows.clear();
                     pending_annotations.clear();
+                    pending_annotations_row = None;
+                    previous_end_row = Some(get_end_row(node));
 
                     // Create separate docstring element if we have class docstrings
                     if !class_docstring_attached && !class_docstring_comments.is_empty() {
@@ -392,12 +654,22 @@ fn extract_tokens_to_reorder(
                             original_text: docstring_text,
                             start_byte: 0,
                             end_byte: 0,
+                            blank_line_before: false,
+                            export_group: 0,
                         });
                         class_docstring_attached = true;
                     }
                 }
             }
             _ => {
+                let first_row = pending_annotations_row.unwrap_or(node.start_position().row);
+                detach_comments(
+                    &mut pending_comments,
+                    &mut pending_comment_rows,
+                    first_row,
+                    previous_end_row,
+                    &mut elements,
+                );
                 if let Some(element) = reorderable_element {
                     // If we haven't attached class docstring yet and this is the first real element,
                     // create a separate docstring element (for cases where there's no extends)
@@ -413,32 +685,31 @@ fn extract_tokens_to_reorder(
                             original_text: docstring_text,
                             start_byte: 0,
                             end_byte: 0,
+                            blank_line_before: false,
+                            export_group: 0,
                         });
                         class_docstring_attached = true;
                     }
 
-                    let mut combined_comments = pending_annotations.clone();
+                    let is_export = matches!(element, GDScriptTokenKind::ExportVariable(_, _));
+                    let mut combined_comments = Vec::new();
+                    if is_export {
+                        combined_comments.append(&mut pending_export_group);
+                    }
+                    combined_comments.extend(pending_annotations.clone());
                     combined_comments.extend(pending_comments.clone());
 
-                    // We store trailing #endregion comments to attach them to
-                    // the most recent function that has a #region comment at
-                    // the top, to move them along with the function when
-                    // reordering
-                    if let Some(region_end) = region_end_comment.take() {
-                        for i in (0..elements.len()).rev() {
-                            if matches!(elements[i].token_kind, GDScriptTokenKind::Method(_, _, _))
-                            {
-                                let has_region = elements[i]
-                                    .attached_comments
-                                    .iter()
-                                    .any(|c| c.trim().starts_with("#region"));
-                                if has_region {
-                                    elements[i].trailing_comments.push(region_end.clone());
-                                    break;
-                                }
-                            }
-                        }
-                    }
+                    // We keep a blank line between two declarations of the same
+                    // kind if there was one in the original code
+                    let first_row = pending_comment_rows
+                        .first()
+                        .map_or(first_row, |(start_row, _)| first_row.min(*start_row));
+                    let blank_line_before = previous_end_row
+                        .is_some_and(|end_row| first_row > end_row + 1)
+                        && elements.last().is_some_and(|previous| {
+                            is_declaration(&previous.token_kind)
+                                && get_token_kind(&previous.token_kind) == get_token_kind(&element)
+                        });
 
                     elements.push(GDScriptTokensWithComments {
                         token_kind: element,
@@ -447,9 +718,14 @@ fn extract_tokens_to_reorder(
                         original_text: text,
                         start_byte: node.start_byte(),
                         end_byte: node.end_byte(),
+                        blank_line_before,
+                        export_group: if is_export { export_group } else { 0 },
                     });
                     pending_comments.clear();
+                    pending_comment_rows.clear();
                     pending_annotations.clear();
+                    pending_annotations_row = None;
+                    previous_end_row = Some(get_end_row(node));
                 } else {
                     // We create unknown element for unhandled nodes to preserve
                     // them. Given how the module works, if we don't do that the
@@ -461,27 +737,197 @@ fn extract_tokens_to_reorder(
                         original_text: text,
                         start_byte: node.start_byte(),
                         end_byte: node.end_byte(),
+                        blank_line_before: false,
+                        export_group: 0,
                     });
                     pending_comments.clear();
+                    pending_comment_rows.clear();
                     pending_annotations.clear();
+                    pending_annotations_row = None;
+                    previous_end_row = Some(get_end_row(node));
                 }
             }
         }
     }
 
+    // Comments at the end of the class stay after the last element, and an
+    // export group without exported variables stays at the end
+    detach_comments(
+        &mut pending_comments,
+        &mut pending_comment_rows,
+        usize::MAX,
+        previous_end_row,
+        &mut elements,
+    );
+    if let Some(text) = pending_export_group.pop() {
+        elements.push(GDScriptTokensWithComments {
+            token_kind: GDScriptTokenKind::Unknown(text.clone()),
+            attached_comments: pending_export_group,
+            trailing_comments: Vec::new(),
+            original_text: text,
+            start_byte: parent.end_byte(),
+            end_byte: parent.end_byte(),
+            blank_line_before: false,
+            export_group: 0,
+        });
+    }
+
+    // The text of elements starts after the indentation of their first line,
+    // so in inner classes, we add it back to move elements around
+    if parent.kind() == "class_body" {
+        let line_start = content[..parent.start_byte()]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        let indent = &content[line_start..parent.start_byte()];
+        for element in &mut elements {
+            indent_element(element, indent);
+        }
+    }
+
     Ok(elements)
 }
 
+/// Returns the last row of a node, ignoring the line break some nodes end with.
+fn get_end_row(node: Node) -> usize {
+    let end = node.end_position();
+    if end.column == 0 && end.row > node.start_position().row {
+        end.row - 1
+    } else {
+        end.row
+    }
+}
+
+/// Returns true for the annotations that start a section of exported
+/// variables in the inspector.
+fn is_export_grouping_annotation(text: &str) -> bool {
+    ["@export_group", "@export_subgroup", "@export_category"]
+        .iter()
+        .any(|annotation| text.starts_with(annotation))
+}
+
+/// Returns true if the element is a declaration or part of the file header,
+/// as opposed to region markers and comments.
+fn is_declaration(token_kind: &GDScriptTokenKind) -> bool {
+    !matches!(
+        token_kind,
+        GDScriptTokenKind::Docstring(_)
+            | GDScriptTokenKind::Unknown(_)
+            | GDScriptTokenKind::RegionStart(_)
+            | GDScriptTokenKind::RegionEnd(_)
+            | GDScriptTokenKind::FloatingComment(_)
+    )
+}
+
+/// Takes the pending comments that aren't right above the next element, which
+/// starts at `next_row`, out of the pending comments. Comments on the last row
+/// of the previous element or right below it stay with it as trailing comments.
+/// The others become floating comments.
+fn detach_comments(
+    pending_comments: &mut Vec<String>,
+    pending_comment_rows: &mut Vec<(usize, usize)>,
+    next_row: usize,
+    previous_end_row: Option<usize>,
+    elements: &mut Vec<GDScriptTokensWithComments>,
+) {
+    // The comments right above the next element are the ones we keep pending
+    let mut attached_start = pending_comments.len();
+    let mut expected_row = next_row;
+    while attached_start > 0 {
+        let (start_row, end_row) = pending_comment_rows[attached_start - 1];
+        if previous_end_row == Some(start_row) || end_row + 1 != expected_row {
+            break;
+        }
+        attached_start -= 1;
+        expected_row = start_row;
+    }
+
+    let detached_rows: Vec<_> = pending_comment_rows.drain(..attached_start).collect();
+    let detached_comments: Vec<_> = pending_comments.drain(..attached_start).collect();
+    let can_trail = elements
+        .last()
+        .is_some_and(|previous| is_declaration(&previous.token_kind));
+    let mut trailing_end_row = previous_end_row.filter(|_| can_trail);
+    let mut floating_end_row = None;
+    for (comment, (start_row, end_row)) in detached_comments.into_iter().zip(detached_rows) {
+        if let Some(row) = trailing_end_row
+            && start_row <= row + 1
+        {
+            let previous = elements.last_mut().unwrap();
+            // A comment on the same line as the element stays on that line
+            if previous_end_row == Some(start_row) && previous.trailing_comments.is_empty() {
+                previous.original_text.push(' ');
+                previous.original_text.push_str(comment.trim());
+            } else {
+                previous.trailing_comments.push(comment);
+            }
+            trailing_end_row = Some(end_row);
+            continue;
+        }
+        trailing_end_row = None;
+
+        // Consecutive comment lines form a single floating comment
+        if let Some(row) = floating_end_row
+            && start_row == row + 1
+            && let Some(previous) = elements.last_mut()
+        {
+            previous.original_text.push('\n');
+            previous.original_text.push_str(&comment);
+            previous.token_kind =
+                GDScriptTokenKind::FloatingComment(previous.original_text.clone());
+        } else {
+            elements.push(GDScriptTokensWithComments {
+                token_kind: GDScriptTokenKind::FloatingComment(comment.clone()),
+                attached_comments: Vec::new(),
+                trailing_comments: Vec::new(),
+                original_text: comment,
+                start_byte: 0,
+                end_byte: 0,
+                blank_line_before: false,
+                export_group: 0,
+            });
+        }
+        floating_end_row = Some(end_row);
+    }
+}
+
+/// Adds the indentation of an inner class's members to the first line of an
+/// element and of its comments. The other lines of an element's text keep
+/// their original indentation.
+fn indent_element(element: &mut GDScriptTokensWithComments, indent: &str) {
+    let indent_first_line = |text: &mut String| text.insert_str(0, indent);
+    match element.token_kind {
+        // Docstrings and floating comments are comments we joined, so we
+        // indent each line
+        GDScriptTokenKind::Docstring(_) | GDScriptTokenKind::FloatingComment(_) => {
+            element.original_text = element
+                .original_text
+                .lines()
+                .map(|line| format!("{}{}", indent, line))
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+        _ => indent_first_line(&mut element.original_text),
+    }
+    element
+        .attached_comments
+        .iter_mut()
+        .chain(element.trailing_comments.iter_mut())
+        .for_each(indent_first_line);
+}
+
 /// This function classifies a parsed tree sitter node into a GDScriptElement.
 fn classify_element(
     node: Node,
     text: &str,
     content: &str,
     is_before_class_declaration: bool,
+    virtual_methods: &[&str],
 ) -> Result<Option<GDScriptTokenKind>, Box<dyn std::error::Error>> {
     match node.kind() {
+        // Godot 3 uses a `tool` keyword instead of the @tool annotation
+        "tool_statement" => Ok(Some(GDScriptTokenKind::ClassAnnotation(text.to_string()))),
         "annotation" => {
-            if is_before_class_declaration {
+            if is_before_class_declaration && !is_export_grouping_annotation(text) {
                 Ok(Some(GDScriptTokenKind::ClassAnnotation(text.to_string())))
             } else {
                 Ok(None)
@@ -524,6 +970,17 @@ fn classify_element(
             Ok(Some(GDScriptTokenKind::Constant(name, is_private)))
         }
         "variable_statement" => classify_variable_statement(node, content),
+        // Godot 3 has dedicated statements for exported and onready variables
+        "export_variable_statement" => {
+            let name = extract_variable_name(node, content)?;
+            let is_private = name.starts_with('_');
+            Ok(Some(GDScriptTokenKind::ExportVariable(name, is_private)))
+        }
+        "onready_variable_statement" => {
+            let name = extract_variable_name(node, content)?;
+            let is_private = name.starts_with('_');
+            Ok(Some(GDScriptTokenKind::OnReadyVariable(name, is_private)))
+        }
         "function_definition" | "constructor_definition" => {
             let name = extract_function_name(node, content)?;
             let is_static = is_static_method(node, content);
@@ -533,7 +990,7 @@ fn classify_element(
                 MethodType::StaticInit
             } else if is_static {
                 MethodType::StaticFunction
-            } else if let Some(priority) = get_builtin_virtual_priority(&name) {
+            } else if let Some(priority) = get_builtin_virtual_priority(&name, virtual_methods) {
                 MethodType::BuiltinVirtual(priority)
             } else {
                 MethodType::Custom
@@ -554,6 +1011,49 @@ fn classify_element(
     }
 }
 
+/// Returns the byte position of the first class_name or extends statement of
+/// a class. Any annotations before this position are class-level annotations.
+/// Inner classes can't have class-level annotations like @tool.
+fn find_first_class_declaration_byte(parent: Node) -> usize {
+    if parent.kind() == "class_body" {
+        return 0;
+    }
+    let mut cursor = parent.walk();
+    parent
+        .named_children(&mut cursor)
+        .find(|node| node.kind() == "class_name_statement" || node.kind() == "extends_statement")
+        .map(|node| node.start_byte())
+        .unwrap_or(usize::MAX)
+}
+
+/// Classifies the members of a class like `extract_tokens_to_reorder()` does,
+/// without attaching comments or reordering anything. `parent` is either the
+/// `source` node or the `class_body` node of an inner class. Comments, region
+/// markers, and other elements that aren't declarations are skipped.
+pub(crate) fn classify_class_members<'a>(
+    parent: Node<'a>,
+    content: &str,
+    virtual_methods: &[&str],
+) -> Result<Vec<(Node<'a>, GDScriptTokenKind)>, Box<dyn std::error::Error>> {
+    let first_class_declaration_byte = find_first_class_declaration_byte(parent);
+    let mut members = Vec::new();
+    let mut cursor = parent.walk();
+    for node in parent.named_children(&mut cursor) {
+        let text = node.utf8_text(content.as_bytes())?;
+        let is_before_class_declaration = node.start_byte() < first_class_declaration_byte;
+        if let Some(token_kind) = classify_element(
+            node,
+            text,
+            content,
+            is_before_class_declaration,
+            virtual_methods,
+        )? {
+            members.push((node, token_kind));
+        }
+    }
+    Ok(members)
+}
+
 /// This function classifies a variable statement into the correct variable type to figure out how to order it.
 fn classify_variable_statement(
     node: Node,
@@ -641,6 +1141,13 @@ fn extract_const_name(node: Node, content: &str) -> Result<String, Box<dyn std::
 /// Returns the name of the variable from a var statement node.
 fn extract_variable_name(node: Node, content: &str) -> Result<String, Box<dyn std::error::Error>> {
     let text = node.utf8_text(content.as_bytes())?;
+    // Godot 3 variables can start with keywords, like `export(int) var speed`
+    let text = match node.kind() {
+        "export_variable_statement" | "onready_variable_statement" => {
+            text.find("var ").map_or(text, |index| &text[index..])
+        }
+        _ => text,
+    };
 
     let Some(name) = text.strip_prefix("var ") else {
         return Ok("unknown_var".to_string());
@@ -687,81 +1194,334 @@ fn is_static_method(node: Node, content: &str) -> bool {
     text.contains("static func")
 }
 
-fn get_builtin_virtual_priority(method_name: &str) -> Option<u8> {
-    BUILTIN_VIRTUAL_METHODS
+fn get_builtin_virtual_priority(method_name: &str, virtual_methods: &[&str]) -> Option<u8> {
+    virtual_methods
         .iter()
         .enumerate()
         // Position in the list is the priority
         .find_map(|(index, name)| (*name == method_name).then_some((index + 1) as u8))
 }
 
-/// Sorts declarations according to the GDScript style guide and returns the ordered list.
+/// Sorts declarations in the configured order, which follows the GDScript style
+/// guide by default, and returns the ordered list. Floating comments keep their
+/// position in the list.
 fn sort_gdscript_tokens(
-    mut tokens: Vec<GDScriptTokensWithComments>,
+    tokens: Vec<GDScriptTokensWithComments>,
+    config: &FormatterConfig,
 ) -> Vec<GDScriptTokensWithComments> {
-    tokens.sort_by(|a, b| {
-        let priority_cmp = a
-            .token_kind
-            .get_priority()
-            .cmp(&b.token_kind.get_priority());
-        if priority_cmp != std::cmp::Ordering::Equal {
-            return priority_cmp;
-        }
-
-        // For methods, we sort by method type
-        if let (GDScriptTokenKind::Method(_, type_a, _), GDScriptTokenKind::Method(_, type_b, _)) =
-            (&a.token_kind, &b.token_kind)
-        {
-            let type_cmp = type_a.cmp(type_b);
-            if type_cmp != std::cmp::Ordering::Equal {
-                return type_cmp;
-            }
+    sort_around_floating_comments(
+        tokens,
+        |token| matches!(token.token_kind, GDScriptTokenKind::FloatingComment(_)),
+        |tokens| tokens.sort_by(|a, b| compare_tokens(a, b, config)),
+    )
+}
 
-            // For built-in virtual methods, we sort them by our priority list
-            if let (MethodType::BuiltinVirtual(p_a), MethodType::BuiltinVirtual(p_b)) =
-                (type_a, type_b)
-            {
-                let builtin_cmp = p_a.cmp(p_b);
-                if builtin_cmp != std::cmp::Ordering::Equal {
-                    return builtin_cmp;
-                }
+/// Sorts the items for which `is_floating_comment` returns false with `sort`,
+/// leaving the other items at their index.
+fn sort_around_floating_comments<T>(
+    items: Vec<T>,
+    is_floating_comment: impl Fn(&T) -> bool,
+    sort: impl FnOnce(&mut Vec<T>),
+) -> Vec<T> {
+    let mut slots = Vec::with_capacity(items.len());
+    let mut sorted = Vec::new();
+    for item in items {
+        if is_floating_comment(&item) {
+            slots.push(Some(item));
+        } else {
+            slots.push(None);
+            sorted.push(item);
+        }
+    }
+    sort(&mut sorted);
+    let mut sorted = sorted.into_iter();
+    slots
+        .into_iter()
+        .map(|slot| slot.unwrap_or_else(|| sorted.next().unwrap()))
+        .collect()
+}
+
+/// Compares two declarations to sort them in the configured order.
+fn compare_tokens(
+    a: &GDScriptTokensWithComments,
+    b: &GDScriptTokensWithComments,
+    config: &FormatterConfig,
+) -> std::cmp::Ordering {
+    let declaration_order = &config.declaration_order;
+    let priority_cmp = a
+        .token_kind
+        .get_priority(declaration_order)
+        .cmp(&b.token_kind.get_priority(declaration_order));
+    if priority_cmp != std::cmp::Ordering::Equal {
+        return priority_cmp;
+    }
+
+    // Exported variables stay in their export group, and the variables of a
+    // group keep their order, as they appear in this order in the inspector
+    if let (GDScriptTokenKind::ExportVariable(_, _), GDScriptTokenKind::ExportVariable(_, _)) =
+        (&a.token_kind, &b.token_kind)
+        && (a.export_group > 0 || b.export_group > 0)
+    {
+        return a.export_group.cmp(&b.export_group);
+    }
+
+    // For methods, we sort by method type
+    if let (GDScriptTokenKind::Method(_, type_a, _), GDScriptTokenKind::Method(_, type_b, _)) =
+        (&a.token_kind, &b.token_kind)
+    {
+        let type_cmp = type_a.cmp(type_b);
+        if type_cmp != std::cmp::Ordering::Equal {
+            return type_cmp;
+        }
+
+        // For built-in virtual methods, we sort them by our priority list
+        if let (MethodType::BuiltinVirtual(p_a), MethodType::BuiltinVirtual(p_b)) = (type_a, type_b)
+        {
+            let builtin_cmp = p_a.cmp(p_b);
+            if builtin_cmp != std::cmp::Ordering::Equal {
+                return builtin_cmp;
             }
         }
+    }
 
-        // Third, sort public before pseudo-private declarations
+    // Third, sort public before pseudo-private declarations
+    if config.private_members_last {
         let privacy_cmp = a.token_kind.is_private().cmp(&b.token_kind.is_private());
         if privacy_cmp != std::cmp::Ordering::Equal {
             return privacy_cmp;
         }
+    }
 
-        // Finally, we handle the top annotations
-        match (&a.token_kind, &b.token_kind) {
-            (
-                GDScriptTokenKind::ClassAnnotation(a_text),
-                GDScriptTokenKind::ClassAnnotation(b_text),
-            ) => {
-                // @tool should generally be at the very top of the script so we give it top priority
-                let a_priority = if a_text.starts_with("@tool") {
-                    0
-                } else if a_text.starts_with("@icon") {
-                    1
-                } else {
-                    2
-                };
-                let b_priority = if b_text.starts_with("@tool") {
-                    0
-                } else if b_text.starts_with("@icon") {
-                    1
-                } else {
-                    2
-                };
-                a_priority.cmp(&b_priority)
+    // Finally, we handle the top annotations
+    match (&a.token_kind, &b.token_kind) {
+        (
+            GDScriptTokenKind::ClassAnnotation(a_text),
+            GDScriptTokenKind::ClassAnnotation(b_text),
+        ) => {
+            // @tool should generally be at the very top of the script so we give it top priority
+            let a_priority = if a_text.starts_with("@tool") {
+                0
+            } else if a_text.starts_with("@icon") {
+                1
+            } else {
+                2
+            };
+            let b_priority = if b_text.starts_with("@tool") {
+                0
+            } else if b_text.starts_with("@icon") {
+                1
+            } else {
+                2
+            };
+            a_priority.cmp(&b_priority)
+        }
+        _ => std::cmp::Ordering::Equal,
+    }
+}
+
+/// A declaration, or a `#region` block we keep together when reordering.
+enum ReorderItem {
+    Element(GDScriptTokensWithComments),
+    Region {
+        start: GDScriptTokensWithComments,
+        items: Vec<ReorderItem>,
+        end: GDScriptTokensWithComments,
+    },
+}
+
+impl ReorderItem {
+    /// Returns the declaration that decides where the item goes when sorting
+    /// regions by their first member. Empty regions and regions with only
+    /// floating comments have none.
+    fn first_element(&self) -> Option<&GDScriptTokensWithComments> {
+        match self {
+            ReorderItem::Element(token) => {
+                (!matches!(token.token_kind, GDScriptTokenKind::FloatingComment(_)))
+                    .then_some(token)
+            }
+            ReorderItem::Region { items, .. } => items.iter().find_map(Self::first_element),
+        }
+    }
+
+    fn flatten_into(self, tokens: &mut Vec<GDScriptTokensWithComments>) {
+        match self {
+            ReorderItem::Element(token) => tokens.push(token),
+            ReorderItem::Region { start, items, end } => {
+                tokens.push(start);
+                for item in items {
+                    item.flatten_into(tokens);
+                }
+                tokens.push(end);
             }
-            _ => std::cmp::Ordering::Equal,
         }
+    }
+}
+
+/// Sorts declarations like `sort_gdscript_tokens()`, keeping the declarations of
+/// each `#region` block together. We sort the declarations inside of each
+/// region, and regions either stay in place or move with their first member
+/// depending on the configuration.
+///
+/// Regions can only stay intact if every #region has a matching #endregion.
+/// Otherwise, we return an error in strict mode, and we treat the region
+/// markers as comments attached to the next declaration in other modes.
+fn sort_gdscript_tokens_with_regions(
+    tokens: Vec<GDScriptTokensWithComments>,
+    config: &FormatterConfig,
+) -> Result<Vec<GDScriptTokensWithComments>, Box<dyn std::error::Error>> {
+    let has_regions = tokens.iter().any(|token| {
+        matches!(
+            token.token_kind,
+            GDScriptTokenKind::RegionStart(_) | GDScriptTokenKind::RegionEnd(_)
+        )
     });
+    if !has_regions {
+        return Ok(sort_gdscript_tokens(tokens, config));
+    }
+
+    let items = match group_regions(tokens.clone()) {
+        Ok(items) => items,
+        Err(error) if config.strict_regions => {
+            return Err(format!("regions can't be kept intact: {}", error).into());
+        }
+        Err(_) => {
+            let tokens = region_markers_to_comments(tokens);
+            return Ok(sort_gdscript_tokens(tokens, config));
+        }
+    };
+
+    let mut sorted = Vec::new();
+    for item in sort_items(items, config) {
+        item.flatten_into(&mut sorted);
+    }
+    Ok(sorted)
+}
+
+/// Groups the declarations between region markers into regions, which can be
+/// nested. Returns an error if a marker doesn't have a matching one.
+fn group_regions(tokens: Vec<GDScriptTokensWithComments>) -> Result<Vec<ReorderItem>, String> {
+    // The last element of the stack holds the items of the innermost open
+    // region, or the top-level items
+    let mut stack: Vec<(Option<GDScriptTokensWithComments>, Vec<ReorderItem>)> =
+        vec![(None, Vec::new())];
+    for token in tokens {
+        match token.token_kind {
+            GDScriptTokenKind::RegionStart(_) => stack.push((Some(token), Vec::new())),
+            GDScriptTokenKind::RegionEnd(_) => {
+                let (Some(start), items) = stack.pop().unwrap() else {
+                    return Err(format!(
+                        "'{}' doesn't close a region",
+                        token.original_text.trim()
+                    ));
+                };
+                stack.last_mut().unwrap().1.push(ReorderItem::Region {
+                    start,
+                    items,
+                    end: token,
+                });
+            }
+            _ => stack
+                .last_mut()
+                .unwrap()
+                .1
+                .push(ReorderItem::Element(token)),
+        }
+    }
+
+    let (start, items) = stack.pop().unwrap();
+    match start {
+        Some(start) => Err(format!("'{}' is never closed", start.original_text.trim())),
+        None => Ok(items),
+    }
+}
 
-    tokens
+/// Sorts items recursively, in the order of the configured region mode.
+fn sort_items(items: Vec<ReorderItem>, config: &FormatterConfig) -> Vec<ReorderItem> {
+    let items: Vec<ReorderItem> = items
+        .into_iter()
+        .map(|item| match item {
+            ReorderItem::Region { start, items, end } => ReorderItem::Region {
+                start,
+                items: sort_items(items, config),
+                end,
+            },
+            element => element,
+        })
+        .collect();
+
+    match config.region_order {
+        RegionOrder::Fixed => {
+            // Regions keep their position, and we sort the other declarations
+            // around them
+            let mut slots = Vec::with_capacity(items.len());
+            let mut elements = Vec::new();
+            for item in items {
+                match item {
+                    ReorderItem::Element(token) => {
+                        slots.push(None);
+                        elements.push(token);
+                    }
+                    region => slots.push(Some(region)),
+                }
+            }
+            let mut elements = sort_gdscript_tokens(elements, config).into_iter();
+            slots
+                .into_iter()
+                .map(|slot| slot.unwrap_or_else(|| ReorderItem::Element(elements.next().unwrap())))
+                .collect()
+        }
+        RegionOrder::FirstMember => sort_around_floating_comments(
+            items,
+            |item| {
+                matches!(item, ReorderItem::Element(token)
+                    if matches!(token.token_kind, GDScriptTokenKind::FloatingComment(_)))
+            },
+            |items| {
+                // Empty regions go last
+                items.sort_by(|a, b| match (a.first_element(), b.first_element()) {
+                    (Some(a), Some(b)) => compare_tokens(a, b, config),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                })
+            },
+        ),
+    }
+}
+
+/// Turns region markers into comments: #region markers stick to the next
+/// declaration, and #endregion markers to the previous one.
+fn region_markers_to_comments(
+    tokens: Vec<GDScriptTokensWithComments>,
+) -> Vec<GDScriptTokensWithComments> {
+    let mut result: Vec<GDScriptTokensWithComments> = Vec::new();
+    let mut pending_comments = Vec::new();
+    for mut token in tokens {
+        match token.token_kind {
+            GDScriptTokenKind::RegionStart(_) => {
+                pending_comments.append(&mut token.attached_comments);
+                pending_comments.push(token.original_text);
+            }
+            GDScriptTokenKind::RegionEnd(_) => {
+                let mut comments = std::mem::take(&mut pending_comments);
+                comments.append(&mut token.attached_comments);
+                comments.push(token.original_text);
+                match result.last_mut() {
+                    Some(previous) => previous.trailing_comments.append(&mut comments),
+                    None => pending_comments = comments,
+                }
+            }
+            _ => {
+                pending_comments.append(&mut token.attached_comments);
+                token.attached_comments = std::mem::take(&mut pending_comments);
+                result.push(token);
+            }
+        }
+    }
+    if let Some(last) = result.last_mut() {
+        last.trailing_comments.append(&mut pending_comments);
+    }
+    result
 }
 
 /// This function takes the sorted declarations/code elements and rebuilds the
@@ -769,11 +1529,53 @@ fn sort_gdscript_tokens(
 fn build_reordered_code(
     tokens: Vec<GDScriptTokensWithComments>,
     _original_content: &str,
+    config: &FormatterConfig,
 ) -> String {
+    let method_spacing = "\n".repeat(config.blank_lines_between_methods);
+    let class_spacing = "\n".repeat(config.blank_lines_between_classes);
     let mut output = String::new();
     let mut previous_token_kind = None;
+    let mut pending_region_starts = Vec::new();
+    let mut follows_floating_comment = false;
 
     for current_token in tokens {
+        match current_token.token_kind {
+            // We write #region markers right before the region's first
+            // declaration, after the spacing this declaration needs
+            GDScriptTokenKind::RegionStart(_) => {
+                pending_region_starts.push(current_token);
+                continue;
+            }
+            // #endregion markers go right after the region's last declaration
+            GDScriptTokenKind::RegionEnd(_) => {
+                for region_start in pending_region_starts.drain(..) {
+                    push_element(&mut output, &region_start);
+                }
+                push_element(&mut output, &current_token);
+                continue;
+            }
+            // Floating comments get a blank line above and below them
+            GDScriptTokenKind::FloatingComment(_) => {
+                if !output.is_empty() {
+                    if matches!(
+                        previous_token_kind,
+                        Some(TokenKind::Method) | Some(TokenKind::InnerClass)
+                    ) {
+                        output.push_str(&method_spacing);
+                    } else {
+                        output.push('\n');
+                    }
+                }
+                for region_start in pending_region_starts.drain(..) {
+                    push_element(&mut output, &region_start);
+                }
+                push_element(&mut output, &current_token);
+                follows_floating_comment = true;
+                continue;
+            }
+            _ => {}
+        }
+
         let current_token_type = get_token_kind(&current_token.token_kind);
         let is_function = matches!(current_token.token_kind, GDScriptTokenKind::Method(_, _, _));
 
@@ -785,6 +1587,8 @@ fn build_reordered_code(
         // double line breaks depending on the context.
         let needs_spacing = if output.is_empty() {
             false
+        } else if follows_floating_comment {
+            true
         } else if let Some(previous_kind) = previous_token_kind {
             if previous_kind != current_token_type {
                 // We're leaving one group of tokens for another (like previous
@@ -799,54 +1603,222 @@ fn build_reordered_code(
             } else {
                 // If we reach here we're seeing the same kind of token as
                 // before, like two regular variables in a row or two signals in
-                // a row - we don't need extra spacing
-                false
+                // a row - we only keep the blank lines between groups of them
+                current_token.blank_line_before
             }
         } else {
             false
         };
 
         if needs_spacing {
-            #[allow(clippy::if_same_then_else)]
             if is_function {
-                output.push_str("\n\n");
-            } else if is_inner_class && previous_token_kind == Some(TokenKind::Method) {
-                output.push_str("\n\n");
-            } else if is_inner_class && previous_token_kind == Some(TokenKind::InnerClass) {
-                output.push_str("\n\n");
+                output.push_str(&method_spacing);
+            } else if is_inner_class
+                && matches!(
+                    previous_token_kind,
+                    Some(TokenKind::Method) | Some(TokenKind::InnerClass)
+                )
+            {
+                output.push_str(&class_spacing);
             } else {
                 output.push('\n');
             }
         }
 
-        // Check and add any comments that were found right before this element
-        // in the original code (like docstrings before a function)
-        for comment in &current_token.attached_comments {
-            output.push_str(comment);
-            if !comment.ends_with('\n') {
-                output.push('\n');
-            }
-        }
-        // Insert the token's original text (function, variable, etc.)
-        output.push_str(&current_token.original_text);
-        if !current_token.original_text.ends_with('\n') {
-            output.push('\n');
-        }
-        // After inserting the token, we also add any trailing comments that were
-        // found right after it in the original code (like #endregion after a function)
-        for comment in &current_token.trailing_comments {
-            output.push_str(comment);
-            if !comment.ends_with('\n') {
-                output.push('\n');
-            }
+        for region_start in pending_region_starts.drain(..) {
+            push_element(&mut output, &region_start);
         }
+        push_element(&mut output, &current_token);
 
         previous_token_kind = Some(current_token_type);
+        follows_floating_comment = false;
     }
 
+    for region_start in pending_region_starts {
+        push_element(&mut output, &region_start);
+    }
     if !output.ends_with('\n') {
         output.push('\n');
     }
 
     output
 }
+
+/// Writes an element along with its comments, each on their own lines.
+fn push_element(output: &mut String, token: &GDScriptTokensWithComments) {
+    // Comments that were found right before this element in the original code
+    // (like docstrings before a function) come first, then the element's
+    // original text, and the comments found right after it (like an #endregion
+    // we couldn't match)
+    let lines = token
+        .attached_comments
+        .iter()
+        .chain(std::iter::once(&token.original_text))
+        .chain(&token.trailing_comments);
+    for text in lines {
+        output.push_str(text);
+        if !text.ends_with('\n') {
+            output.push('\n');
+        }
+    }
+}
+
+/// Finds the misplaced members of a class, following the same rules as
+/// `sort_gdscript_tokens_with_regions()`, then looks into inner classes.
+fn find_misplaced_class_members(
+    parent: Node,
+    content: &str,
+    config: &FormatterConfig,
+    virtual_methods: &[&str],
+    misplaced: &mut Vec<MisplacedDeclaration>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let tokens = extract_tokens_to_reorder(parent, content, config, virtual_methods)?;
+    let items = match group_regions(tokens.clone()) {
+        Ok(items) => items,
+        Err(error) if config.strict_regions => {
+            return Err(format!("regions can't be kept intact: {}", error).into());
+        }
+        Err(_) => region_markers_to_comments(tokens)
+            .into_iter()
+            .map(ReorderItem::Element)
+            .collect(),
+    };
+    find_misplaced_items(&items, content, config, misplaced);
+
+    let mut cursor = parent.walk();
+    for child in parent.named_children(&mut cursor) {
+        if child.kind() != "class_definition" {
+            continue;
+        }
+        let mut class_cursor = child.walk();
+        let body = child
+            .children(&mut class_cursor)
+            .find(|class_child| class_child.kind() == "class_body");
+        if let Some(body) = body {
+            find_misplaced_class_members(body, content, config, virtual_methods, misplaced)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reports each declaration that should come after a declaration that follows
+/// it. Declarations are compared within their region, and regions are compared
+/// with other declarations depending on the region order.
+fn find_misplaced_items(
+    items: &[ReorderItem],
+    content: &str,
+    config: &FormatterConfig,
+    misplaced: &mut Vec<MisplacedDeclaration>,
+) {
+    let mut compared = Vec::new();
+    for item in items {
+        match item {
+            ReorderItem::Element(token) => compared.push(token),
+            ReorderItem::Region { items, .. } => {
+                find_misplaced_items(items, content, config, misplaced);
+                if config.region_order == RegionOrder::FirstMember
+                    && let Some(first) = item.first_element()
+                {
+                    compared.push(first);
+                }
+            }
+        }
+    }
+    // We leave elements we don't know how to sort where they are
+    compared.retain(|token| {
+        !matches!(
+            token.token_kind,
+            GDScriptTokenKind::Unknown(_)
+                | GDScriptTokenKind::Docstring(_)
+                | GDScriptTokenKind::FloatingComment(_)
+        )
+    });
+
+    for (index, token) in compared.iter().enumerate() {
+        let later = compared[index + 1..]
+            .iter()
+            .find(|later| compare_tokens(later, token, config) == std::cmp::Ordering::Less);
+        if let Some(later) = later {
+            misplaced.push(MisplacedDeclaration {
+                line: content[..token.start_byte].matches('\n').count() + 1,
+                message: format!(
+                    "{} must come after {}",
+                    describe_declaration(&token.token_kind),
+                    describe_order_reason(&later.token_kind, &token.token_kind, config)
+                ),
+            });
+        }
+    }
+}
+
+/// Returns a short description of a declaration, like "onready var `_sprite`".
+fn describe_declaration(token_kind: &GDScriptTokenKind) -> String {
+    match token_kind {
+        GDScriptTokenKind::ClassAnnotation(text) => format!("`{}`", text.trim()),
+        GDScriptTokenKind::ClassName(_) => "class_name".to_string(),
+        GDScriptTokenKind::Extends(_) => "extends".to_string(),
+        GDScriptTokenKind::Signal(name, _) => format!("signal `{}`", name),
+        GDScriptTokenKind::Enum(name, _) => format!("enum `{}`", name),
+        GDScriptTokenKind::Constant(name, _) => format!("const `{}`", name),
+        GDScriptTokenKind::StaticVariable(name, _) => format!("static var `{}`", name),
+        GDScriptTokenKind::ExportVariable(name, _) => format!("exported var `{}`", name),
+        GDScriptTokenKind::RegularVariable(name, _) => format!("var `{}`", name),
+        GDScriptTokenKind::OnReadyVariable(name, _) => format!("onready var `{}`", name),
+        GDScriptTokenKind::Method(name, MethodType::StaticFunction, _) => {
+            format!("static func `{}()`", name)
+        }
+        GDScriptTokenKind::Method(name, _, _) => format!("func `{}()`", name),
+        GDScriptTokenKind::InnerClass(name, _) => format!("class `{}`", name),
+        GDScriptTokenKind::Docstring(_)
+        | GDScriptTokenKind::Unknown(_)
+        | GDScriptTokenKind::RegionStart(_)
+        | GDScriptTokenKind::RegionEnd(_)
+        | GDScriptTokenKind::FloatingComment(_) => "this declaration".to_string(),
+    }
+}
+
+/// Explains why `earlier` must come after `later`, which `compare_tokens()`
+/// sorts before it. For example, "regular variables" or "public methods".
+fn describe_order_reason(
+    later: &GDScriptTokenKind,
+    earlier: &GDScriptTokenKind,
+    config: &FormatterConfig,
+) -> String {
+    let declaration_order = &config.declaration_order;
+    if later.get_priority(declaration_order) != earlier.get_priority(declaration_order) {
+        return match later {
+            GDScriptTokenKind::ClassAnnotation(_)
+            | GDScriptTokenKind::ClassName(_)
+            | GDScriptTokenKind::Extends(_) => describe_declaration(later),
+            _ => describe_category(later.get_category()).to_string(),
+        };
+    }
+    match (later, earlier) {
+        (
+            GDScriptTokenKind::Method(name, later_type, _),
+            GDScriptTokenKind::Method(_, earlier_type, _),
+        ) if later_type != earlier_type || matches!(later_type, MethodType::BuiltinVirtual(_)) => {
+            format!("`{}()`", name)
+        }
+        (GDScriptTokenKind::ClassAnnotation(_), _) => describe_declaration(later),
+        _ => format!("public {}", describe_category(later.get_category())),
+    }
+}
+
+/// Returns the name of a category of declarations, as used in messages.
+fn describe_category(category: DeclarationCategory) -> &'static str {
+    match category {
+        DeclarationCategory::Signals => "signals",
+        DeclarationCategory::Enums => "enums",
+        DeclarationCategory::Constants => "constants",
+        DeclarationCategory::StaticVariables => "static variables",
+        DeclarationCategory::ExportVariables => "exported variables",
+        DeclarationCategory::Variables => "regular variables",
+        DeclarationCategory::OnreadyVariables => "onready variables",
+        DeclarationCategory::StaticInit => "`_static_init()`",
+        DeclarationCategory::StaticMethods => "static methods",
+        DeclarationCategory::VirtualMethods => "built-in virtual methods",
+        DeclarationCategory::Methods => "methods",
+        DeclarationCategory::InnerClasses => "inner classes",
+    }
+}
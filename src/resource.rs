@@ -0,0 +1,146 @@
+//! This module finds the GDScript code embedded in Godot scene and resource
+//! files (.tscn and .tres) to format and lint built-in scripts.
+//!
+//! Godot stores built-in scripts in `[sub_resource type="GDScript"]` sections,
+//! with the code in an escaped, multi-line string: `script/source = "..."`. We
+//! only ever rewrite the content of these strings, so the rest of the file stays
+//! byte-identical.
+use std::{ops::Range, path::Path};
+
+use crate::FormatterConfig;
+use crate::formatter::format_gdscript_with_config;
+
+/// A GDScript embedded in a scene or resource file.
+pub struct EmbeddedScript {
+    /// Byte range of the escaped source code in the file, between the quotes.
+    pub range: Range<usize>,
+    /// Line of the file where the source code starts, starting from 1.
+    pub line: usize,
+    /// The unescaped source code.
+    pub source: String,
+}
+
+/// Returns true if the path is a Godot scene or text resource file.
+pub fn is_resource_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == "tscn" || ext == "tres")
+}
+
+/// Returns the scripts embedded in the content of a .tscn or .tres file, in
+/// the order they appear in the file.
+pub fn find_embedded_scripts(content: &str) -> Vec<EmbeddedScript> {
+    let mut scripts = Vec::new();
+    // A .tres file can itself be a GDScript resource, in which case the source
+    // code is in its [resource] section
+    let mut is_gdscript_resource = false;
+    let mut is_in_gdscript_section = false;
+
+    let mut offset = 0;
+    while offset < content.len() {
+        let line_end = content[offset..]
+            .find('\n')
+            .map_or(content.len(), |index| offset + index + 1);
+        let line = &content[offset..line_end];
+
+        if line.starts_with('[') {
+            let is_gdscript_type = line.contains("type=\"GDScript\"");
+            if line.starts_with("[gd_resource") {
+                is_gdscript_resource = is_gdscript_type;
+            }
+            is_in_gdscript_section = (line.starts_with("[sub_resource") && is_gdscript_type)
+                || (line.starts_with("[resource]") && is_gdscript_resource);
+        } else if is_in_gdscript_section && line.starts_with("script/source = \"") {
+            let start = offset + "script/source = \"".len();
+            let Some(end) = find_string_end(content, start) else {
+                break;
+            };
+            scripts.push(EmbeddedScript {
+                range: start..end,
+                line: content[..offset].matches('\n').count() + 1,
+                source: unescape_string(&content[start..end]),
+            });
+            // The string can span many lines, so we continue after it
+            offset = end;
+            continue;
+        }
+
+        offset = line_end;
+    }
+
+    scripts
+}
+
+/// Formats every script embedded in the content of a .tscn or .tres file and
+/// returns the new file content. Scripts that are already formatted keep their
+/// original escaped text.
+pub fn format_resource_with_config(
+    content: &str,
+    config: &FormatterConfig,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut output = String::with_capacity(content.len());
+    let mut last_end = 0;
+
+    for script in find_embedded_scripts(content) {
+        let formatted = format_gdscript_with_config(&script.source, config).map_err(|error| {
+            format!(
+                "Failed to format the built-in script at line {}: {}",
+                script.line, error
+            )
+        })?;
+
+        output.push_str(&content[last_end..script.range.start]);
+        if formatted == script.source {
+            output.push_str(&content[script.range.clone()]);
+        } else {
+            output.push_str(&escape_string(&formatted));
+        }
+        last_end = script.range.end;
+    }
+
+    output.push_str(&content[last_end..]);
+    Ok(output)
+}
+
+/// Returns the byte index of the quote that closes the string starting at
+/// `start`, skipping escaped characters.
+fn find_string_end(content: &str, start: usize) -> Option<usize> {
+    let mut bytes = content.bytes().enumerate().skip(start);
+    while let Some((index, byte)) = bytes.next() {
+        match byte {
+            b'\\' => {
+                bytes.next();
+            }
+            b'"' => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Unescapes a string from a Godot text resource.
+fn unescape_string(escaped: &str) -> String {
+    let mut unescaped = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('b') => unescaped.push('\u{8}'),
+            Some('f') => unescaped.push('\u{c}'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Escapes source code the way Godot does when it saves a built-in script:
+/// only backslashes and quotes get escaped, new lines stay as they are.
+fn escape_string(source: &str) -> String {
+    source.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,19 @@
+@export var speed := 10.0
+@export_range(0, 100)
+var health := 50
+@onready var label: Label = $Label
+@warning_ignore("unused_private_class_variable")
+var _cache := {}
+@export_range(0.0, 1000.0, 0.5, "or_greater", "suffix:px/s")
+var maximum_horizontal_speed_of_the_character := 400.0
+
+@export_group("Movement")
+@export var jump := 4.0
+
+
+@rpc func sync_position() -> void:
+	pass
+
+
+@rpc("any_peer") func request_spawn() -> void:
+	pass
@@ -0,0 +1,20 @@
+@export
+var speed := 10.0
+@export_range(0, 100)
+var health := 50
+@onready
+var label: Label = $Label
+@warning_ignore("unused_private_class_variable")
+var _cache := {}
+
+@export_group("Movement")
+@export
+var jump := 4.0
+
+
+@rpc func sync_position() -> void:
+	pass
+
+
+@rpc("any_peer") func request_spawn() -> void:
+	pass
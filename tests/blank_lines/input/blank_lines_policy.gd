@@ -0,0 +1,23 @@
+extends Node
+var a = 1
+
+
+var b = 2
+
+
+
+var c = 3
+func one():
+	pass
+func two():
+	print(1)
+
+
+
+	print(2)
+
+	print(3)
+class Inner:
+	var x = 1
+	func inner_method():
+		pass
@@ -0,0 +1,44 @@
+tool
+extends Node
+
+signal died
+
+export(int, 0, 100) var speed = 10
+var health = 100 setget set_health
+onready var label = $Label
+
+
+func _init():
+	pass
+
+
+func _ready():
+	connect(
+		"died",
+		self,
+		"_on_died",
+		[health],
+		CONNECT_ONESHOT
+	)
+
+
+func _enter_tree():
+	pass
+
+
+func _process(delta):
+	label.text = str(speed * delta)
+
+
+func set_health(value):
+	health = value
+
+
+func _on_died(
+		previous_health
+):
+	var healths = [
+		previous_health,
+		health,
+	]
+	print(healths)
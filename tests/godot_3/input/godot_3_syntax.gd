@@ -0,0 +1,40 @@
+tool
+extends Node
+
+signal died
+
+onready var label = $Label
+export(int, 0, 100) var speed = 10
+var health = 100 setget set_health
+
+
+func _process(delta):
+	label.text = str(speed * delta)
+
+
+func _ready():
+	connect("died", self, "_on_died",
+		[health],
+		CONNECT_ONESHOT,)
+
+
+func _enter_tree():
+	pass
+
+
+func _init():
+	pass
+
+
+func set_health(value):
+	health = value
+
+
+func _on_died(
+	previous_health,
+):
+	var healths = [
+		previous_health,
+		health
+	]
+	print(healths)
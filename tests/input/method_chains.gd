@@ -0,0 +1,10 @@
+func _ready() -> void:
+	var tween := create_tween().set_trans(Tween.TRANS_SINE).set_ease(Tween.EASE_OUT).set_parallel(true)
+	tween.tween_property($Sprite2D, "modulate", Color(1.0, 1.0, 1.0, 0.0), 0.5).set_delay(0.25).as_relative()
+	var short := create_tween().set_trans(Tween.TRANS_SINE)
+	get_tree().create_timer(1.0) \
+	.timeout.connect(_on_timeout)
+	add_child(
+		Label.new()
+		.set_text("hello")
+	)
@@ -1,13 +1,30 @@
-use gdscript_formatter::FormatterConfig;
+use gdscript_formatter::code_blocks::{DocumentKind, format_code_blocks_with_config};
 use gdscript_formatter::formatter::format_gdscript_with_config;
 use gdscript_formatter::linter::{GDScriptLinter, LinterConfig};
+use gdscript_formatter::migrate::migrate_gdscript;
+use gdscript_formatter::resource::format_resource_with_config;
+use gdscript_formatter::{
+    AnnotationPlacement, DeclarationCategory, FormatterConfig, GodotVersion, RegionOrder,
+    TrailingCommas,
+};
 use similar::{ChangeTag, TextDiff};
 use std::fs;
 use std::path::Path;
 
 test_each_file::test_each_path! { in "./tests/input" => test_file }
 test_each_file::test_each_path! { in "./tests/reorder_code/input" => test_reorder_file }
+test_each_file::test_each_path! { in "./tests/reorder_order/input" as reorder_order => test_reorder_order_file }
+test_each_file::test_each_path! { in "./tests/reorder_regions/input" as reorder_regions => test_reorder_regions_file }
 test_each_file::test_each_path! { in "./tests/lint/input" as lint => test_lint_file  }
+test_each_file::test_each_path! { in "./tests/normalize_numbers/input" as normalize_numbers => test_normalize_numbers_file }
+test_each_file::test_each_path! { in "./tests/blank_lines/input" as blank_lines => test_blank_lines_file }
+test_each_file::test_each_path! { in "./tests/trailing_commas/input" as trailing_commas => test_trailing_commas_file }
+test_each_file::test_each_path! { in "./tests/reflow_comments/input" as reflow_comments => test_reflow_comments_file }
+test_each_file::test_each_path! { in "./tests/annotations/input" as annotations => test_annotations_file }
+test_each_file::test_each_path! { in "./tests/resources/input" as resources => test_resource_file }
+test_each_file::test_each_path! { in "./tests/code_blocks/input" as code_blocks => test_code_blocks_file }
+test_each_file::test_each_path! { in "./tests/godot_3/input" as godot_3 => test_godot_3_file }
+test_each_file::test_each_path! { in "./tests/migrate/input" as migrate => test_migrate_file }
 
 fn make_whitespace_visible(s: &str) -> String {
     s.replace(' ', "·")
@@ -57,6 +74,170 @@ fn test_reorder_file(file_path: &Path) {
     );
 }
 
+fn test_reorder_order_file(file_path: &Path) {
+    test_file_with_config(
+        file_path,
+        &FormatterConfig {
+            reorder_code: true,
+            declaration_order: vec![
+                DeclarationCategory::Constants,
+                DeclarationCategory::Signals,
+                DeclarationCategory::Variables,
+                DeclarationCategory::ExportVariables,
+                DeclarationCategory::Methods,
+                DeclarationCategory::VirtualMethods,
+            ],
+            virtual_methods: Some(vec!["_ready".to_string(), "_init".to_string()]),
+            private_members_last: false,
+            ..Default::default()
+        },
+        true,
+    );
+}
+
+fn test_reorder_regions_file(file_path: &Path) {
+    test_file_with_config(
+        file_path,
+        &FormatterConfig {
+            reorder_code: true,
+            region_order: RegionOrder::FirstMember,
+            ..Default::default()
+        },
+        true,
+    );
+}
+
+fn test_normalize_numbers_file(file_path: &Path) {
+    test_file_with_config(
+        file_path,
+        &FormatterConfig {
+            normalize_numbers: true,
+            group_digits: Some(5),
+            ..Default::default()
+        },
+        true,
+    );
+}
+
+fn test_blank_lines_file(file_path: &Path) {
+    test_file_with_config(
+        file_path,
+        &FormatterConfig {
+            blank_lines_between_methods: 1,
+            blank_lines_between_classes: 3,
+            max_blank_lines_between_properties: 2,
+            blank_lines_after_extends: 2,
+            max_blank_lines_in_functions: 0,
+            ..Default::default()
+        },
+        true,
+    );
+}
+
+fn test_trailing_commas_file(file_path: &Path) {
+    test_file_with_config(
+        file_path,
+        &FormatterConfig {
+            trailing_commas: TrailingCommas::Never,
+            ..Default::default()
+        },
+        true,
+    );
+}
+
+fn test_reflow_comments_file(file_path: &Path) {
+    test_file_with_config(
+        file_path,
+        &FormatterConfig {
+            reflow_comments: true,
+            safe: true,
+            ..Default::default()
+        },
+        true,
+    );
+}
+
+fn test_annotations_file(file_path: &Path) {
+    // annotations_inline.gd tests the opposite placements of the other files
+    let inline_variables = file_path.file_stem() == Some("annotations_inline".as_ref());
+    let (variable_annotations, function_annotations) = if inline_variables {
+        (AnnotationPlacement::Inline, AnnotationPlacement::OwnLine)
+    } else {
+        (AnnotationPlacement::OwnLine, AnnotationPlacement::Inline)
+    };
+    test_file_with_config(
+        file_path,
+        &FormatterConfig {
+            variable_annotations,
+            function_annotations,
+            ..Default::default()
+        },
+        true,
+    );
+
+    // The default placement must give the same output as before the placement
+    // options existed
+    let default_expected_path = file_path
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("default/")
+        .join(file_path.file_name().unwrap());
+    let input_content = fs::read_to_string(file_path).unwrap();
+    let expected_content = fs::read_to_string(&default_expected_path).expect(&format!(
+        "Failed to read {}",
+        default_expected_path.display()
+    ));
+    let result = format_gdscript_with_config(&input_content, &FormatterConfig::default())
+        .expect(&format!("Failed to format {}", file_path.display()));
+    assert_formatted_eq(
+        &result,
+        &expected_content,
+        file_path,
+        "Formatting with the default annotation placement doesn't match the baseline output",
+    );
+}
+
+fn test_resource_file(file_path: &Path) {
+    let config = FormatterConfig::default();
+    test_file_with_formatter(
+        file_path,
+        |content| format_resource_with_config(content, &config),
+        true,
+    );
+}
+
+fn test_code_blocks_file(file_path: &Path) {
+    let config = FormatterConfig::default();
+    let kind = DocumentKind::from_path(file_path).expect("not a documentation file");
+    test_file_with_formatter(
+        file_path,
+        |content| Ok(format_code_blocks_with_config(content, kind, &config).content),
+        true,
+    );
+}
+
+fn test_godot_3_file(file_path: &Path) {
+    test_file_with_config(
+        file_path,
+        &FormatterConfig {
+            godot_version: GodotVersion::Godot3,
+            reorder_code: true,
+            ..Default::default()
+        },
+        true,
+    );
+}
+
+fn test_migrate_file(file_path: &Path) {
+    test_file_with_formatter(
+        file_path,
+        |content| Ok(migrate_gdscript(content)?.content),
+        true,
+    );
+}
+
 fn test_lint_file(file_path: &Path) {
     let file_name = file_path.file_name().expect("path is not a file path");
     let file_stem = file_path.file_stem().expect("path is not a file path");
@@ -75,7 +256,12 @@ fn test_lint_file(file_path: &Path) {
     let expected_content = fs::read_to_string(&expected_path)
         .expect(&format!("Failed to read {}", expected_path.display()));
 
-    let mut linter = GDScriptLinter::new(LinterConfig::default()).expect(&format!(
+    // Rules that are off by default run on the file named after them
+    let config = LinterConfig {
+        enabled_rules: [file_stem.to_string_lossy().replace('_', "-")].into(),
+        ..Default::default()
+    };
+    let mut linter = GDScriptLinter::new(config).expect(&format!(
         "Failed to create linter for {}",
         input_path.display()
     ));
@@ -115,6 +301,18 @@ fn test_lint_file(file_path: &Path) {
 }
 
 fn test_file_with_config(file_path: &Path, config: &FormatterConfig, check_idempotence: bool) {
+    test_file_with_formatter(
+        file_path,
+        |content| format_gdscript_with_config(content, config),
+        check_idempotence,
+    );
+}
+
+fn test_file_with_formatter(
+    file_path: &Path,
+    format: impl Fn(&str) -> Result<String, Box<dyn std::error::Error>>,
+    check_idempotence: bool,
+) {
     let file_name = file_path.file_name().expect("path is not a file path");
 
     let input_path = file_path;
@@ -131,8 +329,8 @@ fn test_file_with_config(file_path: &Path, config: &FormatterConfig, check_idemp
     let expected_content = fs::read_to_string(&expected_path)
         .expect(&format!("Failed to read {}", expected_path.display()));
 
-    let result = format_gdscript_with_config(&input_content, config)
-        .expect(&format!("Failed to format {}", input_path.display()));
+    let result =
+        format(&input_content).expect(&format!("Failed to format {}", input_path.display()));
 
     assert_formatted_eq(
         &result,
@@ -142,8 +340,8 @@ fn test_file_with_config(file_path: &Path, config: &FormatterConfig, check_idemp
     );
 
     if check_idempotence {
-        let second_result = format_gdscript_with_config(&result, config)
-            .expect(&format!("Failed to format {}", input_path.display()));
+        let second_result =
+            format(&result).expect(&format!("Failed to format {}", input_path.display()));
         assert_formatted_eq(
             &second_result,
             &result,
@@ -0,0 +1,23 @@
+#region Good
+
+func good(enabled):
+	if enabled:
+		print("enabled")
+	while true:
+		break
+
+#endregion
+
+#region Bad
+
+func bad(value):
+	if true:
+		print(value)
+	elif false:
+		print("never")
+	while false:
+		print("never")
+	if (false):
+		print("never")
+
+#endregion
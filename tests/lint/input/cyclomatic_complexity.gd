@@ -0,0 +1,28 @@
+#region Good
+
+func good(value):
+	if value > 0 and value < 10:
+		print(value)
+
+#endregion
+
+#region Bad
+
+func bad(value, other):
+	if value > 0 and other > 0:
+		print("positive")
+	elif value < 0 or other < 0:
+		print("negative")
+	for i in range(value):
+		print(i)
+	while value > 10:
+		value -= 1
+	var symbol = "+" if value > 0 else "-"
+	match other:
+		0:
+			print("zero")
+		_:
+			print(symbol)
+	return value > 5 and other > 5
+
+#endregion
@@ -0,0 +1,40 @@
+#region Good
+signal health_changed
+
+const MAX_HEALTH = 100
+
+var health = MAX_HEALTH
+@onready var _sprite = $Sprite2D
+
+
+func _ready():
+	pass
+
+
+func heal():
+	pass
+
+#endregion
+
+#region Bad
+@onready var _label = $Label
+var speed = 10
+
+
+func move():
+	pass
+
+
+func _init():
+	pass
+
+
+class Inner:
+	func _helper():
+		pass
+
+
+	func run():
+		pass
+
+#endregion
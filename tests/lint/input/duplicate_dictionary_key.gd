@@ -0,0 +1,15 @@
+#region Good
+
+const COLORS = {"red": Color.RED, "blue": Color.BLUE}
+
+#endregion
+
+#region Bad
+
+const SIZES = {"small": 1, "large": 3, "small": 2}
+const OPTIONS = {
+	volume = 1.0,
+	"volume": 0.5,
+}
+
+#endregion
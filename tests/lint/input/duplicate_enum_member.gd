@@ -0,0 +1,25 @@
+#region Good
+
+enum State {
+	IDLE,
+	RUNNING,
+	JUMPING = 5,
+}
+
+#endregion
+
+#region Bad
+
+enum Direction {
+	UP,
+	DOWN,
+	UP,
+}
+
+enum Flag {
+	NONE = 0,
+	VISIBLE = 1,
+	ENABLED = 0x1,
+}
+
+#endregion
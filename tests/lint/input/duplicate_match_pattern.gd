@@ -0,0 +1,27 @@
+#region Good
+
+func good(value):
+	match value:
+		0:
+			print("zero")
+		1, 2:
+			print("small")
+		_:
+			print("other")
+
+#endregion
+
+#region Bad
+
+func bad(value):
+	match value:
+		0:
+			print("zero")
+		1, 0:
+			print("one")
+		"text":
+			print("text")
+		"text":
+			print("other text")
+
+#endregion
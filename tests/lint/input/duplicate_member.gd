@@ -0,0 +1,33 @@
+#region Good
+
+signal health_changed
+
+var health = 100
+
+
+func take_damage(amount):
+	health -= amount
+	health_changed.emit()
+
+#endregion
+
+#region Bad
+
+signal died
+signal died
+
+var armor = 0
+
+
+func heal():
+	health += 1
+
+
+func heal():
+	health += 2
+
+
+func armor():
+	return 1
+
+#endregion
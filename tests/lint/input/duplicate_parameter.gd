@@ -0,0 +1,18 @@
+#region Good
+
+func good(first, second):
+	print(first, second)
+
+#endregion
+
+#region Bad
+
+func bad(_skipped, other, _skipped):
+	print(other)
+
+
+func bad_lambda():
+	var callback = func(item, item): print(item)
+	callback.call(1, 2)
+
+#endregion
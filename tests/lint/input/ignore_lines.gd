@@ -16,6 +16,7 @@ func bad():
 	obj._private_method()
 
 	const badConstName = "this line is also very long but will NOT be ignored by the comment above this"
+	print(badConstName)
 
 
 func ignored():
@@ -33,6 +34,7 @@ func ignored():
 
 	# gdlint-ignore-next-line constant-name,max-line-length
 	const anotherBadConstName = "this line is also very long but it will be ignored by the comment above this"
+	print(anotherBadConstName)
 
 	# gdlint-ignore-next-line
 	const totallyIgnored = "this line is also very long but it will be ignored by the comment above this"
@@ -0,0 +1,18 @@
+tool
+extends Node
+
+export(int) var speed = 10
+onready var label = $Label
+var health = 100 setget set_health
+
+
+remote func sync_position():
+	pass
+
+
+func set_health(value):
+	health = value
+
+
+func wait():
+	yield(get_tree(), "idle_frame")
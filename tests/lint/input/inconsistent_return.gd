@@ -0,0 +1,37 @@
+#region Good
+
+func good(value):
+	if value > 0:
+		return 1
+	return 0
+
+
+func good_match(value):
+	match value:
+		0:
+			return "zero"
+		_:
+			return "other"
+
+
+func good_loop(items):
+	while true:
+		if items.is_empty():
+			return null
+		items.pop_back()
+
+#endregion
+
+#region Bad
+
+func bad_end(value):
+	if value > 0:
+		return 1
+
+
+func bad_bare_return(value):
+	if value > 0:
+		return
+	return value
+
+#endregion
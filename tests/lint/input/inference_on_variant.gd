@@ -0,0 +1,26 @@
+extends Node
+
+#region Good
+
+func good(scores: Array[int]) -> void:
+	var first := scores[0]
+	var child := get_child(0)
+	var sprite: Sprite2D = $Sprite2D
+	print(first, child, sprite)
+
+#endregion
+
+#region Bad
+
+var inventory := {}
+
+
+func bad(items) -> void:
+	var item := items[0]
+	var count := inventory["count"]
+	var sprite := $Sprite2D
+	var label := get_node("Label")
+	var value := inventory.get("value")
+	print(item, count, sprite, label, value)
+
+#endregion
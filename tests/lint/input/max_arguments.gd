@@ -0,0 +1,13 @@
+#region Good
+
+func good(a, b, c):
+	print(a, b, c)
+
+#endregion
+
+#region Bad
+
+func bad(a, b, c, d, e, f, g, h, i, j, k):
+	print(a, b, c, d, e, f, g, h, i, j, k)
+
+#endregion
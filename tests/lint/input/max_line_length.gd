@@ -13,9 +13,9 @@ func bad():
 	var array = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24]
 
 	for i in array:
-		for j in array:
-			for k in array:
-				for l in array:
-					for m in array:
-						print("Here is another very long line, this one deeply nested inside of ton of loops")
+		for j in i:
+			for k in j:
+				for l in k:
+					for m in l:
+						print(m, "Here is another very long line, this one deeply nested inside of the loops")
 #endregion
@@ -0,0 +1,21 @@
+#region Good
+
+func good(values):
+	for value in values:
+		if value > 0:
+			print(value)
+
+#endregion
+
+#region Bad
+
+func bad(value):
+	if value > 0:
+		if value > 1:
+			if value > 2:
+				if value > 3:
+					if value > 4:
+						if value > 5:
+							print(value)
+
+#endregion
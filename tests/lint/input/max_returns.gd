@@ -0,0 +1,28 @@
+#region Good
+
+func good(value):
+	if value > 0:
+		return 1
+	return 0
+
+#endregion
+
+#region Bad
+
+func bad(value):
+	match value:
+		0:
+			return "zero"
+		1:
+			return "one"
+		2:
+			return "two"
+		3:
+			return "three"
+		4:
+			return "four"
+		5:
+			return "five"
+	return "many"
+
+#endregion
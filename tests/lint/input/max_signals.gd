@@ -0,0 +1,24 @@
+#region Good
+
+class FewSignals:
+	signal started
+	signal finished
+
+#endregion
+
+#region Bad
+
+class ManySignals:
+	signal started
+	signal paused
+	signal resumed
+	signal stopped
+	signal finished
+	signal health_changed
+	signal health_depleted
+	signal item_picked
+	signal item_dropped
+	signal level_up
+	signal died
+
+#endregion
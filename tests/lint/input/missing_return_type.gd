@@ -0,0 +1,25 @@
+#region Good
+
+func _init():
+	print("init")
+
+
+func good() -> void:
+	print("good")
+
+
+func get_speed() -> float:
+	return 1.0
+
+#endregion
+
+#region Bad
+
+static func bad_static(value: int):
+	return value * 2
+
+
+func bad():
+	print("bad")
+
+#endregion
@@ -0,0 +1,15 @@
+#region Good
+
+func good(values):
+	print(len(values))
+
+#endregion
+
+#region Bad
+
+func bad(str):
+	var max = str.length()
+	for len in range(max):
+		print(len)
+
+#endregion
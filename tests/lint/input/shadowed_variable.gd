@@ -0,0 +1,18 @@
+#region Good
+var speed = 10
+
+
+func good(new_speed):
+	speed = new_speed
+
+#endregion
+
+#region Bad
+var health = 100
+
+
+func bad(speed):
+	var health = speed
+	print(health)
+
+#endregion
@@ -0,0 +1,17 @@
+extends Node2D
+
+#region Good
+
+func good(new_position):
+	position = new_position
+
+#endregion
+
+#region Bad
+
+func bad(position):
+	var scale = position * 2
+	for name in ["a", "b"]:
+		print(name, scale)
+
+#endregion
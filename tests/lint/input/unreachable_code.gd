@@ -0,0 +1,35 @@
+#region Good
+
+func good(items):
+	for item in items:
+		if item == null:
+			continue
+		print(item)
+	while true:
+		if items.is_empty():
+			break
+		items.pop_back()
+	print("done")
+
+#endregion
+
+#region Bad
+
+func bad_after_return(value):
+	return value
+	print(value)
+	print("never")
+
+
+func bad_after_break(items):
+	for item in items:
+		break
+		print(item)
+
+
+func bad_after_infinite_loop():
+	while true:
+		print("forever")
+	print("never")
+
+#endregion
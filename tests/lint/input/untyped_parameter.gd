@@ -0,0 +1,30 @@
+#region Good
+
+func good_typed(speed: float, direction: Vector2) -> void:
+	print(speed, direction)
+
+
+func good_inferred(amount := 10) -> void:
+	print(amount)
+
+
+func good_typed_default(amount: int = 10) -> void:
+	print(amount)
+
+
+func good_lambda() -> void:
+	var callback := func(value): print(value)
+	callback.call(1)
+
+#endregion
+
+#region Bad
+
+func bad_untyped(speed, direction) -> void:
+	print(speed, direction)
+
+
+func bad_default(amount = 10) -> void:
+	print(amount)
+
+#endregion
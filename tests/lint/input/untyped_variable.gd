@@ -0,0 +1,23 @@
+#region Good
+
+var health: int = 100
+var speed := 10.0
+
+
+func good() -> void:
+	var direction: Vector2 = Vector2.RIGHT
+	var distance := 2.0
+	print(health, speed, direction * distance)
+
+#endregion
+
+#region Bad
+
+var score = 0
+
+
+func bad() -> void:
+	var multiplier = 2
+	print(score * multiplier)
+
+#endregion
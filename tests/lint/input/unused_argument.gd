@@ -8,6 +8,11 @@ func set_selection(node: Node) -> void:
 	something.get_selection().add_node(node)
 	something.edit_node(node)
 
+
+func good_captured(value):
+	var callback = func(): print(value)
+	callback.call()
+
 #endregion
 
 #region Bad
@@ -15,4 +20,13 @@ func set_selection(node: Node) -> void:
 func bad(used_arg, unused_arg):
 	print(used_arg)
 
+
+func bad_shadowed(value):
+	var callback = func(value): print(value)
+	callback.call(1)
+
+
+func bad_attribute(key):
+	print(dictionary.key)
+
 #endregion
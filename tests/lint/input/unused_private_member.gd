@@ -0,0 +1,64 @@
+#region Good
+signal _used_signal
+
+const _USED_CONSTANT = 10
+
+var speed = 0: set = _set_speed
+var _used_variable = _USED_CONSTANT
+
+
+func _ready():
+	_used_signal.connect(Callable(self, "_handle_signal"))
+	print(self._used_variable)
+	call_deferred("_deferred_call")
+
+
+func _set_speed(value):
+	speed = value
+
+
+func _deferred_call():
+	pass
+
+
+func _handle_signal():
+	pass
+
+#endregion
+
+#region Bad
+signal _unused_signal
+
+const _UNUSED_CONSTANT = 20
+
+var _unused_variable = 0
+var _shadowed_variable = 0
+var _written_variable = 0
+
+
+func _unused_method():
+	pass
+
+
+func _on_button_pressed():
+	pass
+
+
+func _recursive_method(count):
+	if count > 0:
+		_recursive_method(count - 1)
+
+
+func use_other(other):
+	# gdlint-ignore-next-line shadowed-variable
+	var _shadowed_variable = 1
+	# gdlint-ignore-next-line private-access
+	print(_shadowed_variable, other._unused_variable)
+	_written_variable = 1
+	self._written_variable = 2
+
+
+class _UnusedClass:
+	pass
+
+#endregion
@@ -0,0 +1,36 @@
+#region Good
+
+func good(items):
+	var total = 0
+	for item in items:
+		total += item
+	const LIMIT = 10
+	print(total > LIMIT)
+
+
+func good_ignored(items):
+	var _unused = 0
+	const _UNUSED = 1
+	for _item in items: # gdlint-ignore loop-variable-name
+		pass
+
+
+func good_captured():
+	var count = 0
+	var callback = func(): print(count)
+	callback.call()
+
+#endregion
+
+#region Bad
+
+func bad(items):
+	var unused = 0
+	var result = compute()
+	const UNUSED = 1
+	var assigned = 0
+	assigned = 2
+	for item in items:
+		print("item")
+
+#endregion
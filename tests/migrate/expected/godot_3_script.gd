@@ -0,0 +1,45 @@
+@tool
+extends Node2D
+
+signal health_changed(health)
+
+@export var speed = 200.0
+@export_range(0, 100) var max_health: int = 100
+@export_file("*.json") var save_path: String
+@export var bullet_scene: PackedScene
+@onready var sprite = $Sprite
+var health = 100:
+	set = set_health, get = get_health
+var shield = 0:
+	get = get_shield
+
+
+func _ready():
+	connect("health_changed", _on_health_changed)
+	$Timer.connect("timeout", sprite.hide, CONNECT_ONESHOT)
+	connect("health_changed", $HUD.update_health.bind(max_health))
+	await get_tree().create_timer(1.0).timeout
+	await spawn_bullets()
+
+
+func spawn_bullets():
+	var bullet = bullet_scene.instance()
+	add_child(bullet)
+	await health_changed
+
+
+func set_health(value):
+	health = value
+	emit_signal("health_changed", health)
+
+
+func get_health():
+	return health
+
+
+func get_shield():
+	return shield
+
+
+func _on_health_changed(new_health):
+	print("Health: %s" % new_health)
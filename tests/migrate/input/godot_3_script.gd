@@ -0,0 +1,43 @@
+tool
+extends Node2D
+
+signal health_changed(health)
+
+export var speed = 200.0
+export(int, 0, 100) var max_health = 100
+export(String, FILE, "*.json") var save_path
+export(PackedScene) var bullet_scene
+onready var sprite = $Sprite
+var health = 100 setget set_health, get_health
+var shield = 0 setget , get_shield
+
+
+func _ready():
+	connect("health_changed", self, "_on_health_changed")
+	$Timer.connect("timeout", sprite, "hide", [], CONNECT_ONESHOT)
+	connect("health_changed", $HUD, "update_health", [max_health])
+	yield(get_tree().create_timer(1.0), "timeout")
+	yield(spawn_bullets(), "completed")
+
+
+func spawn_bullets():
+	var bullet = bullet_scene.instance()
+	add_child(bullet)
+	yield(self, "health_changed")
+
+
+func set_health(value):
+	health = value
+	emit_signal("health_changed", health)
+
+
+func get_health():
+	return health
+
+
+func get_shield():
+	return shield
+
+
+func _on_health_changed(new_health):
+	print("Health: %s" % new_health)
@@ -0,0 +1,17 @@
+var hex_color = 0xfb8c00
+var hex_mixed = 0xabcdef
+var binary = 0b1010_0101
+var leading_zero = 0.5
+var trailing_zero = 5.0
+var exponent = 1e5
+var exponent_float = 1.5e-3
+var large_number = 1_000_000
+var badly_grouped = 1_000_000
+var large_float = 1_234_567.891
+var small_number = 1000
+var string_number = "0XFF .5 1000000"
+
+
+func _ready():
+	position.x = -0.25
+	print(0xff) # 0XFF stays as is in comments
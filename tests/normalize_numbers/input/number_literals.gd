@@ -0,0 +1,17 @@
+var hex_color = 0XFB8C00
+var hex_mixed = 0xAbCdEf
+var binary = 0B1010_0101
+var leading_zero = .5
+var trailing_zero = 5.
+var exponent = 1E5
+var exponent_float = 1.5E-3
+var large_number = 1000000
+var badly_grouped = 10_00_000
+var large_float = 1234567.891
+var small_number = 1000
+var string_number = "0XFF .5 1000000"
+
+
+func _ready():
+	position.x = -.25
+	print(0XFF) # 0XFF stays as is in comments
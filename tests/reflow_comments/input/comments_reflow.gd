@@ -0,0 +1,30 @@
+#!/usr/bin/env -S godot --headless --script
+extends Node
+## A node that spawns enemies around the player at regular intervals, following the
+## wave settings defined in the spawner resource.
+##
+##Use [method start] to begin spawning. Each wave uses [code]wave_size * difficulty[/code] enemies, rounded down.
+## @tutorial(Spawning): https://example.com/spawning
+
+#region Settings
+#This comment needs a space
+#regional settings
+#format later
+#fmtstr
+var wave_size := 3 #inline comment
+#endregion
+
+
+#gdlint:ignore=max-line-length
+## Spawns one wave.
+## [codeblock]
+## spawner.spawn_wave(   3 )
+## [/codeblock]
+## Options:
+##   - [code]count[/code]: the number of enemies to spawn in this wave, before the difficulty multiplier is applied.
+##   - [code]delay[/code]: unused.
+## @tutorial(Waves): https://example.com/waves
+##     Explains how waves scale with difficulty.
+func spawn_wave(count: int) -> void:
+	##This long doc comment inside a function is also reflowed to fit in the configured line width of the formatter.
+	pass
@@ -0,0 +1,27 @@
+extends Node
+
+# Tuning values for the character controller.
+
+const MAX_SPEED = 300.0
+const FRICTION = 900.0
+
+const JUMP_HEIGHT = 64.0
+const GRAVITY = 980.0
+const _ACCELERATION = 1200.0
+
+@export var health = 100 # Current health
+@export_group("Movement")
+@export var _jump_velocity = 400.0
+@export var speed = 200.0
+@export_group("Visuals")
+@export var color = Color.WHITE
+
+var velocity_cache = Vector2.ZERO
+
+
+func _ready():
+	pass
+
+
+func _physics_process(delta):
+	pass
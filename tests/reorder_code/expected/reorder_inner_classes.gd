@@ -0,0 +1,34 @@
+extends Node
+
+
+func _ready():
+	add_child(Enemy.new())
+
+
+class Enemy:
+	extends CharacterBody2D
+
+	signal died
+
+	var speed = 100.0
+
+
+	func _init():
+		pass
+
+
+	func _ready():
+		pass
+
+
+	# Moves toward the target
+	func chase(target):
+		pass
+
+
+	class State:
+		var name = ""
+
+
+		func enter():
+			pass
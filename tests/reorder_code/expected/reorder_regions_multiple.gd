@@ -0,0 +1,29 @@
+extends Node
+
+#region Movement
+var speed = 10.0
+
+
+func _physics_process(delta):
+	move()
+
+
+func move():
+	pass
+#endregion
+
+var name = ""
+
+#region Health
+signal died
+
+var health = 100
+
+
+func take_damage(amount):
+	health -= amount
+#endregion
+
+
+func _ready():
+	pass
@@ -0,0 +1,32 @@
+extends Node
+
+
+func _ready():
+	add_child(Enemy.new())
+
+
+class Enemy:
+	extends CharacterBody2D
+
+	func _ready():
+		pass
+
+
+	# Moves toward the target
+	func chase(target):
+		pass
+
+
+	class State:
+		func enter():
+			pass
+
+
+		var name = ""
+
+	var speed = 100.0
+	signal died
+
+
+	func _init():
+		pass
@@ -0,0 +1,29 @@
+extends Node
+
+const MAX = 3
+
+signal changed
+
+var _cache = {}
+
+@export var speed = 10
+
+
+func _private_helper():
+	pass
+
+
+func public_api():
+	pass
+
+
+func _process(delta):
+	pass
+
+
+func _ready():
+	pass
+
+
+func _init():
+	pass
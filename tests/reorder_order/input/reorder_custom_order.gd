@@ -0,0 +1,26 @@
+extends Node
+
+var _cache = {}
+signal changed
+@export var speed = 10
+const MAX = 3
+
+
+func _init():
+	pass
+
+
+func _private_helper():
+	pass
+
+
+func _ready():
+	pass
+
+
+func public_api():
+	pass
+
+
+func _process(delta):
+	pass
@@ -0,0 +1,21 @@
+extends Node
+
+#region Movement
+func move():
+	pass
+var speed = 10.0
+func _physics_process(delta):
+	move()
+#endregion
+
+func _ready():
+	pass
+
+#region Health
+signal died
+var health = 100
+func take_damage(amount):
+	health -= amount
+#endregion
+
+var name = ""
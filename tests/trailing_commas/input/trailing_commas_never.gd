@@ -0,0 +1,26 @@
+enum Direction {
+	UP,
+	DOWN,
+}
+
+const ITEMS = [
+	"sword",
+	"shield",
+]
+
+const TEXTURE = preload(
+	"res://icon.svg",
+)
+
+var stats = {
+	"health": 10,
+	"mana": 5,
+}
+
+
+func _ready() -> void:
+	print(
+		"a",
+		"b",
+	)
+	var short = [1, 2, 3]